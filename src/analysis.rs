@@ -0,0 +1,299 @@
+//! Metrics of a maze, to grade its difficulty and compare generators.
+//!
+//! Cells are classified by their open passages: dead ends have one,
+//! corridors two facing each other, turns two at a right angle and junctions
+//! three or more.
+
+use std::cmp::Reverse;
+use std::fmt;
+
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+use crate::mouse::{distances_from, shortest_path};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MazeMetrics {
+    pub number_of_cells: usize,
+    pub dead_ends: usize,
+    pub corridors: usize,
+    pub turns: usize,
+    pub junctions: usize,
+    /// Average number of steps between two cells which are not corridors or turns
+    pub average_corridor_length: f64,
+    /// Average number of steps from a dead end back to a junction: long
+    /// winding branches make a maze harder than many short spurs
+    pub average_dead_end_length: f64,
+    /// Cells of the solution, start and goal included, 0 if there is none
+    pub solution_length: usize,
+    /// Cells of the solution where there is more than one way forward
+    pub decision_points: usize,
+}
+
+impl MazeMetrics {
+    pub fn dead_end_percentage(&self) -> f64 {
+        percentage(self.dead_ends, self.number_of_cells)
+    }
+
+    /// Part of the maze the solution goes through
+    pub fn solution_ratio(&self) -> f64 {
+        self.solution_length as f64 / self.number_of_cells.max(1) as f64
+    }
+}
+
+fn percentage(count: usize, total: usize) -> f64 {
+    100.0 * count as f64 / total.max(1) as f64
+}
+
+impl fmt::Display for MazeMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cells = self.number_of_cells;
+        writeln!(f, "cells: {}", cells)?;
+        writeln!(
+            f,
+            "dead ends: {} ({:.1}%)",
+            self.dead_ends,
+            self.dead_end_percentage()
+        )?;
+        writeln!(
+            f,
+            "corridors: {} ({:.1}%)",
+            self.corridors,
+            percentage(self.corridors, cells)
+        )?;
+        writeln!(
+            f,
+            "turns: {} ({:.1}%)",
+            self.turns,
+            percentage(self.turns, cells)
+        )?;
+        writeln!(
+            f,
+            "junctions: {} ({:.1}%)",
+            self.junctions,
+            percentage(self.junctions, cells)
+        )?;
+        writeln!(
+            f,
+            "average corridor length: {:.2}",
+            self.average_corridor_length
+        )?;
+        writeln!(
+            f,
+            "average dead end length: {:.2}",
+            self.average_dead_end_length
+        )?;
+        writeln!(
+            f,
+            "solution length: {} ({:.1}% of the cells)",
+            self.solution_length,
+            100.0 * self.solution_ratio()
+        )?;
+        writeln!(f, "decision points: {}", self.decision_points)
+    }
+}
+
+fn is_passage_cell(grid: &Grid, pos: Position) -> bool {
+    grid.get_neighbors_of_pos(pos).len() == 2
+}
+
+// Walks from `from`, which isn't a passage cell, through `first` and the
+// passage cells after it until a cell which isn't one. Returns that cell and
+// the number of steps.
+fn walk_passage(grid: &Grid, from: Position, first: Position) -> (Position, usize) {
+    let (mut previous, mut current) = (from, first);
+    let mut steps = 1;
+    while is_passage_cell(grid, current) {
+        let next = grid
+            .get_neighbors_of_pos(current)
+            .into_iter()
+            .find(|neighbor| *neighbor != previous)
+            .unwrap_or(previous);
+        (previous, current) = (current, next);
+        steps += 1;
+    }
+    (current, steps)
+}
+
+fn average(values: &[usize]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<usize>() as f64 / values.len() as f64
+}
+
+/// Measures the maze, solving it from `start_pos` to `end_pos`
+pub fn analyze(grid: &Grid, start_pos: Position, end_pos: Position) -> MazeMetrics {
+    let positions = grid.get_cells_positions();
+    let mut metrics = MazeMetrics {
+        number_of_cells: positions.len(),
+        dead_ends: 0,
+        corridors: 0,
+        turns: 0,
+        junctions: 0,
+        average_corridor_length: 0.0,
+        average_dead_end_length: 0.0,
+        solution_length: 0,
+        decision_points: 0,
+    };
+
+    let mut corridor_lengths: Vec<usize> = Vec::new();
+    let mut dead_end_lengths: Vec<usize> = Vec::new();
+    for pos in positions {
+        let neighbors = grid.get_neighbors_of_pos(pos);
+        match neighbors.len() {
+            0 => {}
+            1 => metrics.dead_ends += 1,
+            2 if neighbors[0].x == neighbors[1].x || neighbors[0].y == neighbors[1].y => {
+                metrics.corridors += 1
+            }
+            2 => metrics.turns += 1,
+            _ => metrics.junctions += 1,
+        }
+        if neighbors.len() == 2 {
+            continue;
+        }
+
+        // each corridor is walked from both of its ends, which keeps the average
+        corridor_lengths.extend(
+            neighbors
+                .iter()
+                .map(|neighbor| walk_passage(grid, pos, *neighbor).1),
+        );
+        // a maze made of a single corridor has no branch
+        if let [neighbor] = neighbors[..] {
+            let (end, steps) = walk_passage(grid, pos, neighbor);
+            if grid.get_neighbors_of_pos(end).len() > 2 {
+                dead_end_lengths.push(steps);
+            }
+        }
+    }
+    metrics.average_corridor_length = average(&corridor_lengths);
+    metrics.average_dead_end_length = average(&dead_end_lengths);
+
+    let solution = shortest_path(start_pos, end_pos, grid);
+    metrics.solution_length = solution.len();
+    // the way back doesn't count as a choice, except at the start
+    metrics.decision_points = solution
+        .iter()
+        .take(solution.len().saturating_sub(1))
+        .enumerate()
+        .filter(|(i, pos)| {
+            let ways_forward = grid.get_neighbors_of_pos(**pos).len() - usize::from(*i > 0);
+            ways_forward > 1
+        })
+        .count();
+    metrics
+}
+
+// Farthest cell from `start_pos`, the first one row by row on ties
+fn farthest_from(start_pos: Position, grid: &Grid) -> (Position, usize) {
+    distances_from(start_pos, grid)
+        .into_iter()
+        .max_by_key(|(pos, distance)| (*distance, Reverse((pos.y, pos.x))))
+        .unwrap_or((start_pos, 0))
+}
+
+/// Two cells as far apart as possible, and the number of steps between them.
+///
+/// In a perfect maze the cell farthest from any cell is one end of a longest
+/// path, so two breadth-first searches are enough. Loops, from braiding or
+/// rooms, break that property: the cells found are then far apart but a pair
+/// farther apart may exist, finding it would take a search from every cell.
+pub fn diameter(grid: &Grid) -> (Position, Position, usize) {
+    let first = grid
+        .get_cells_positions()
+        .first()
+        .copied()
+        .unwrap_or(Position { x: 0, y: 0 });
+    let (one_end, _) = farthest_from(first, grid);
+    let (other_end, distance) = farthest_from(one_end, grid);
+    (one_end, other_end, distance)
+}
+
+/// Where the start and the goal of a maze are placed
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Endpoints {
+    /// Top left and bottom right cells, or the first and last cells row by
+    /// row when the corners are void
+    Corners,
+    /// Ends of the diameter of the maze, for the longest possible solution.
+    /// Only exact in a perfect maze, see `diameter`.
+    Farthest,
+}
+
+impl Endpoints {
+    pub const ALL: [Endpoints; 2] = [Endpoints::Corners, Endpoints::Farthest];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Endpoints::Corners => "corners",
+            Endpoints::Farthest => "farthest",
+        }
+    }
+
+    /// Start and goal cells of the maze
+    pub fn place(&self, grid: &Grid) -> (Position, Position) {
+        match self {
+            Endpoints::Corners => {
+                let positions = grid.get_cells_positions();
+                let start_pos = positions.first().copied();
+                let end_pos = positions.last().copied();
+                let origin = Position { x: 0, y: 0 };
+                (start_pos.unwrap_or(origin), end_pos.unwrap_or(origin))
+            }
+            Endpoints::Farthest => {
+                let (start_pos, end_pos, _) = diameter(grid);
+                (start_pos, end_pos)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::ascii;
+
+    // perfect maze whose only junction is (1, 0), with dead ends 1, 2 and 8
+    // steps away from it
+    const MAZE: &str = "\
++--+--+--+--+
+|        |  |
++--+  +  +  +
+|     |  |  |
++  +--+--+  +
+|           |
++--+--+--+--+
+";
+
+    #[test]
+    fn finds_the_diameter_of_a_known_maze() {
+        let grid = ascii::parse(MAZE).unwrap();
+        // from (0, 0) the farthest cell is (3, 0), 9 steps away, and (2, 1) is
+        // 10 steps away from it: farther than the first search went
+        let (one_end, other_end) = (Position { x: 3, y: 0 }, Position { x: 2, y: 1 });
+        assert_eq!(diameter(&grid), (one_end, other_end, 10));
+        assert_eq!(Endpoints::Farthest.place(&grid), (one_end, other_end));
+        assert_eq!(
+            Endpoints::Corners.place(&grid),
+            (Position { x: 0, y: 0 }, Position { x: 3, y: 2 })
+        );
+    }
+
+    #[test]
+    fn measures_a_known_maze() {
+        let grid = ascii::parse(MAZE).unwrap();
+        let metrics = analyze(&grid, Position { x: 0, y: 0 }, Position { x: 3, y: 2 });
+        assert_eq!(metrics.number_of_cells, 12);
+        assert_eq!(metrics.dead_ends, 3);
+        assert_eq!(metrics.corridors, 3);
+        assert_eq!(metrics.turns, 5);
+        assert_eq!(metrics.junctions, 1);
+        // 1, 2 and 8 steps, each walked from both ends
+        assert!((metrics.average_corridor_length - 11.0 / 3.0).abs() < 1e-9);
+        assert!((metrics.average_dead_end_length - 11.0 / 3.0).abs() < 1e-9);
+        assert_eq!(metrics.solution_length, 8);
+        // only the junction offers a choice on the way
+        assert_eq!(metrics.decision_points, 1);
+    }
+}
@@ -0,0 +1,696 @@
+use std::fs;
+use std::io::{IsTerminal, Write};
+use std::path::Path;
+
+use explorer::analysis::{Endpoints, analyze};
+use explorer::constants::game_constants::{NUMBER_OF_CELLS_X, NUMBER_OF_CELLS_Y};
+use explorer::format::raster::{Image, read_mask, read_maze};
+use explorer::format::recording::{
+    DEFAULT_FRAMES_PER_SECOND, GENERATION_STEPS_PER_SECOND, GifRecorder, MAX_FRAMES_PER_SECOND,
+    Pace, SOLVING_STEPS_PER_SECOND, record_generation, record_solving,
+};
+use explorer::format::{Format, ImageStyle, Rgb, WriteOptions, ascii, binary, edges, json};
+use explorer::maze_generator::braid::braid;
+use explorer::maze_generator::sparsify::sparsify;
+use explorer::maze_generator::terrain::scatter_terrain;
+use explorer::maze_generator::{GenerationEvent, Generator, RoomOptions};
+use explorer::model::cell::Position;
+use explorer::model::grid::Grid;
+use explorer::model::mask::Mask;
+use explorer::mouse::{Solver, distances_from, erase_backtracking};
+use explorer::uniformity::check_uniformity;
+use explorer::validation::check_perfect_maze;
+
+pub const USAGE: &str = "\
+usage: explorer                      open the viewer (needs the gui feature)
+       explorer generate [options]   generate a maze without opening a window
+       explorer import <file> [options]
+                                     read a maze from a file and write it like generate does
+       explorer check <file> [--cell-size <pixels>]
+                                     check that a file holds a perfect maze: every cell
+                                     reachable and no loops
+       explorer uniformity [--generator <name>] [--width <cells>] [--height <cells>]
+                           [--mask <file>] [--seed <number>] [--samples <number>]
+                                     generate many small mazes and test whether each possible
+                                     maze is as likely, on grids up to about 4x4 (default 3x3,
+                                     20 samples per possible maze)
+       explorer help                 show this message
+
+Maze files are read according to their extension: .json, .amaz for the binary format, .txt
+for the ascii format, .edges for the edges format, or .png with black walls on white, which
+needs the --cell-size of the image.
+
+Masks give mazes any shape. They are read from a .png with one pixel per cell, the dark
+pixels being the cells of the maze, or from a text file with one character per cell, `.` and
+spaces being void. The cells must be connected.
+
+options (import refuses the generation ones, from --width to --terrain, --generation-trace
+and --record-generation):
+  --width <cells>          number of cells on the x axis, up to 65535 (default 20)
+  --height <cells>         number of cells on the y axis, up to 65535 (default 20)
+  --mask <file>            only generate the cells of a mask, whose size replaces --width and
+                           --height
+  --generator <name>       wilson, backtracker or binary-tree (default wilson)
+  --seed <number>          seed of the generation (default random)
+  --rooms <count>          carve this many open rectangular rooms, or <min>-<max>, before the
+                           corridors around them, fewer if they don't fit (default 0)
+  --room-size <cells>      side of the rooms, or <min>-<max> (default 3-6)
+  --room-doors <number>    passages from each room to the corridors (default 2)
+  --sparsify <passes>      remove every dead end this many times over, leaving sparse
+                           corridors (default 0)
+  --braid <fraction>       open this fraction of the dead ends, from 0 to 1, which adds loops
+                           (default 0, a perfect maze)
+  --terrain <max cost>     scatter pools of rough terrain costing up to <max cost> to walk into
+                           (default 1, every cell costs 1)
+  --solve <solver>         also solve from the start to the goal with bfs, dfs or dijkstra,
+                           the only one looking for the cheapest path instead of the shortest
+  --endpoints <placement>  start and goal at the top left and bottom right corners, or at the
+                           two cells farthest apart, which may miss the farthest pair in a
+                           maze with loops: corners or farthest (default corners)
+  --format <name>          output format: edges, ascii, unicode, blocks, json, binary, svg or png
+                           (default edges)
+  --color <when>           colors in the unicode and blocks formats: auto, always or never
+                           (default auto, colored when writing to a terminal)
+  --cell-size <pixels>     side of a cell in the image formats (default 20, or the imported
+                           image's)
+  --wall-thickness <pixels>
+                           thickness of the walls in the image formats (default 2)
+  --wall-color <#rrggbb>   color of the walls in the image formats
+  --background-color <#rrggbb>
+                           color of the cells in the image formats
+  --solution-color <#rrggbb>
+                           color of the solution in the image formats
+  --heatmap <cell>         color the cells by their distance to a cell in the image formats,
+                           the start or <x>,<y>
+  --output <file>          write to a file instead of stdout
+  --generation-trace <file>
+                           also write every step of the generation to a JSON file
+  --metrics <file>         also write dead ends, corridors, solution length and other metrics
+                           of the maze to a text file
+  --record-generation <path>
+                           record the generation animation to a .gif file, or to numbered png
+                           frames in the <path> directory
+  --record-solving <path>  record the solver's walk the same way, needs --solve
+  --frame-rate <frames>    frames per second of the recordings, from 1 to 50 (default 50)";
+
+enum HeatmapOrigin {
+    Start,
+    Cell(Position),
+}
+
+struct GenerateOptions {
+    number_of_cells_x: u16,
+    number_of_cells_y: u16,
+    mask: Option<Mask>,
+    generator: Generator,
+    seed: u64,
+    rooms: RoomOptions,
+    sparsify_passes: usize,
+    braid: f64,
+    max_cost: u32,
+    solver: Option<Solver>,
+    endpoints: Endpoints,
+    format: Format,
+    colors: Option<bool>,
+    cell_size: Option<u32>,
+    wall_thickness: Option<f64>,
+    wall_color: Option<Rgb>,
+    background_color: Option<Rgb>,
+    solution_color: Option<Rgb>,
+    heatmap_origin: Option<HeatmapOrigin>,
+    output: Option<String>,
+    generation_trace_output: Option<String>,
+    metrics_output: Option<String>,
+    generation_recording: Option<String>,
+    solving_recording: Option<String>,
+    frames_per_second: u32,
+}
+
+// bounds of the grid dimensions, which the formats store on 16 bits
+const MIN_NUMBER_OF_CELLS: u16 = 1;
+const MAX_NUMBER_OF_CELLS: u16 = u16::MAX;
+
+// options of generate which only shape the generation, not the maze written
+const GENERATION_OPTIONS: [&str; 13] = [
+    "--width",
+    "--height",
+    "--mask",
+    "--generator",
+    "--seed",
+    "--rooms",
+    "--room-size",
+    "--room-doors",
+    "--sparsify",
+    "--braid",
+    "--terrain",
+    "--generation-trace",
+    "--record-generation",
+];
+
+fn parse_number_of_cells(value: &str) -> Result<u16, String> {
+    match value.parse::<u16>() {
+        Ok(cells) if (MIN_NUMBER_OF_CELLS..=MAX_NUMBER_OF_CELLS).contains(&cells) => Ok(cells),
+        _ => Err(format!(
+            "invalid number of cells '{}', expected {} to {}",
+            value, MIN_NUMBER_OF_CELLS, MAX_NUMBER_OF_CELLS
+        )),
+    }
+}
+
+fn parse_generator(value: &str) -> Result<Generator, String> {
+    Generator::ALL
+        .into_iter()
+        .find(|generator| generator.name() == value)
+        .ok_or_else(|| format!("unknown generator '{}'", value))
+}
+
+fn parse_cell_size(value: &str) -> Result<u32, String> {
+    match value.parse::<u32>() {
+        Ok(cell_size) if cell_size > 0 => Ok(cell_size),
+        _ => Err(format!("invalid cell size '{}'", value)),
+    }
+}
+
+fn parse_color(value: &str) -> Result<Rgb, String> {
+    let invalid = || format!("invalid color '{}', expected #rrggbb", value);
+    let hex = value.strip_prefix('#').ok_or_else(invalid)?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return Err(invalid());
+    }
+    let component =
+        |i: usize| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).map_err(|_| invalid());
+    Ok([component(0)?, component(1)?, component(2)?])
+}
+
+fn parse_position(value: &str) -> Result<Position, String> {
+    let invalid = || format!("invalid cell '{}', expected <x>,<y>", value);
+    let (x, y) = value.split_once(',').ok_or_else(invalid)?;
+    Ok(Position {
+        x: x.parse().map_err(|_| invalid())?,
+        y: y.parse().map_err(|_| invalid())?,
+    })
+}
+
+// a number or an inclusive <min>-<max> range
+fn parse_range(value: &str, what: &str) -> Result<(usize, usize), String> {
+    let invalid = || {
+        format!(
+            "invalid {} '{}', expected <number> or <min>-<max>",
+            what, value
+        )
+    };
+    let (min, max) = value.split_once('-').unwrap_or((value, value));
+    let (min, max) = (
+        min.parse::<usize>().map_err(|_| invalid())?,
+        max.parse::<usize>().map_err(|_| invalid())?,
+    );
+    if min > max {
+        return Err(invalid());
+    }
+    Ok((min, max))
+}
+
+fn parse_generate_options(args: &[String]) -> Result<GenerateOptions, String> {
+    let mut options = GenerateOptions {
+        number_of_cells_x: NUMBER_OF_CELLS_X,
+        number_of_cells_y: NUMBER_OF_CELLS_Y,
+        mask: None,
+        generator: Generator::Wilson,
+        seed: rand::random(),
+        rooms: RoomOptions {
+            min_count: 0,
+            max_count: 0,
+            ..RoomOptions::default()
+        },
+        sparsify_passes: 0,
+        braid: 0.0,
+        max_cost: 1,
+        solver: None,
+        endpoints: Endpoints::Corners,
+        format: Format::Edges,
+        colors: None,
+        cell_size: None,
+        wall_thickness: None,
+        wall_color: None,
+        background_color: None,
+        solution_color: None,
+        heatmap_origin: None,
+        output: None,
+        generation_trace_output: None,
+        metrics_output: None,
+        generation_recording: None,
+        solving_recording: None,
+        frames_per_second: DEFAULT_FRAMES_PER_SECOND,
+    };
+
+    let mut args = args.iter();
+    while let Some(option) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value after {}", option))?;
+        match option.as_str() {
+            "--width" => options.number_of_cells_x = parse_number_of_cells(value)?,
+            "--height" => options.number_of_cells_y = parse_number_of_cells(value)?,
+            "--mask" => options.mask = Some(read_mask_file(value)?),
+            "--generator" => options.generator = parse_generator(value)?,
+            "--seed" => {
+                options.seed = value
+                    .parse()
+                    .map_err(|_| format!("invalid seed '{}'", value))?
+            }
+            "--rooms" => {
+                (options.rooms.min_count, options.rooms.max_count) =
+                    parse_range(value, "number of rooms")?
+            }
+            "--room-size" => {
+                let (min_size, max_size) = parse_range(value, "room size")?;
+                if min_size == 0 {
+                    return Err(format!("invalid room size '{}'", value));
+                }
+                (options.rooms.min_size, options.rooms.max_size) = (min_size, max_size);
+            }
+            "--room-doors" => {
+                options.rooms.doors = match value.parse::<usize>() {
+                    Ok(doors) if doors > 0 => doors,
+                    _ => return Err(format!("invalid number of doors '{}'", value)),
+                }
+            }
+            "--sparsify" => {
+                options.sparsify_passes = value
+                    .parse::<usize>()
+                    .map_err(|_| format!("invalid number of passes '{}'", value))?
+            }
+            "--braid" => {
+                options.braid = match value.parse::<f64>() {
+                    Ok(fraction) if (0.0..=1.0).contains(&fraction) => fraction,
+                    _ => return Err(format!("invalid braid fraction '{}'", value)),
+                }
+            }
+            "--terrain" => {
+                options.max_cost = match value.parse::<u32>() {
+                    Ok(max_cost) if max_cost > 0 => max_cost,
+                    _ => return Err(format!("invalid maximum cost '{}'", value)),
+                }
+            }
+            "--solve" => {
+                options.solver = Some(
+                    Solver::ALL
+                        .into_iter()
+                        .find(|solver| solver.name() == value)
+                        .ok_or_else(|| format!("unknown solver '{}'", value))?,
+                )
+            }
+            "--endpoints" => {
+                options.endpoints = Endpoints::ALL
+                    .into_iter()
+                    .find(|endpoints| endpoints.name() == value)
+                    .ok_or_else(|| format!("unknown endpoints placement '{}'", value))?
+            }
+            "--format" => {
+                options.format =
+                    Format::from_name(value).ok_or_else(|| format!("unknown format '{}'", value))?
+            }
+            "--color" => {
+                options.colors = match value.as_str() {
+                    "auto" => None,
+                    "always" => Some(true),
+                    "never" => Some(false),
+                    _ => return Err(format!("invalid color mode '{}'", value)),
+                }
+            }
+            "--cell-size" => options.cell_size = Some(parse_cell_size(value)?),
+            "--wall-thickness" => {
+                options.wall_thickness = match value.parse::<f64>() {
+                    Ok(thickness) if thickness > 0.0 => Some(thickness),
+                    _ => return Err(format!("invalid wall thickness '{}'", value)),
+                }
+            }
+            "--wall-color" => options.wall_color = Some(parse_color(value)?),
+            "--background-color" => options.background_color = Some(parse_color(value)?),
+            "--solution-color" => options.solution_color = Some(parse_color(value)?),
+            "--heatmap" => {
+                options.heatmap_origin = Some(match value.as_str() {
+                    "start" => HeatmapOrigin::Start,
+                    _ => HeatmapOrigin::Cell(parse_position(value)?),
+                })
+            }
+            "--output" => options.output = Some(value.to_owned()),
+            "--generation-trace" => options.generation_trace_output = Some(value.to_owned()),
+            "--metrics" => options.metrics_output = Some(value.to_owned()),
+            "--record-generation" => options.generation_recording = Some(value.to_owned()),
+            "--record-solving" => options.solving_recording = Some(value.to_owned()),
+            "--frame-rate" => {
+                options.frames_per_second = match value.parse::<u32>() {
+                    Ok(frames) if (1..=MAX_FRAMES_PER_SECOND).contains(&frames) => frames,
+                    _ => return Err(format!("invalid frame rate '{}'", value)),
+                }
+            }
+            _ => return Err(format!("unknown option {}", option)),
+        }
+    }
+    if options.solving_recording.is_some() && options.solver.is_none() {
+        return Err("--record-solving needs --solve".to_owned());
+    }
+    Ok(options)
+}
+
+fn write_output(path: &str, bytes: &[u8]) -> Result<(), String> {
+    fs::write(path, bytes).map_err(|e| format!("cannot write {}: {}", path, e))
+}
+
+// Style of the images, from the default one and the options overriding it
+fn image_style(default: ImageStyle, options: &GenerateOptions) -> Result<ImageStyle, String> {
+    let mut style = default;
+    if let Some(cell_size) = options.cell_size {
+        style.cell_size = cell_size;
+    }
+    if let Some(thickness) = options.wall_thickness {
+        style.wall_ratio = thickness / (2.0 * style.cell_size as f64);
+    }
+    if style.wall_ratio >= 0.5 {
+        return Err("walls are too thick for the size of the cells".to_owned());
+    }
+    style.wall_color = options.wall_color.unwrap_or(style.wall_color);
+    style.cell_color = options.background_color.unwrap_or(style.cell_color);
+    style.solution_color = options.solution_color.unwrap_or(style.solution_color);
+    Ok(style)
+}
+
+/// Frames of an animation, going to an animated GIF or to numbered PNG files
+enum FrameOutput {
+    Gif(String, GifRecorder),
+    Png {
+        directory: String,
+        number_of_frames: usize,
+    },
+}
+
+impl FrameOutput {
+    fn new(
+        path: &str,
+        grid: &Grid,
+        style: &ImageStyle,
+        frames_per_second: u32,
+    ) -> Result<FrameOutput, String> {
+        if path.ends_with(".gif") {
+            let (number_of_cells_x, number_of_cells_y) = grid.get_number_of_cells_x_y();
+            let recorder = GifRecorder::new(
+                number_of_cells_x as u32 * style.cell_size,
+                number_of_cells_y as u32 * style.cell_size,
+                frames_per_second,
+            )?;
+            return Ok(FrameOutput::Gif(path.to_owned(), recorder));
+        }
+        fs::create_dir_all(path).map_err(|e| format!("cannot create {}: {}", path, e))?;
+        Ok(FrameOutput::Png {
+            directory: path.to_owned(),
+            number_of_frames: 0,
+        })
+    }
+
+    fn add_frame(&mut self, image: &Image) -> Result<(), String> {
+        match self {
+            FrameOutput::Gif(_, recorder) => recorder.add_frame(image),
+            FrameOutput::Png {
+                directory,
+                number_of_frames,
+            } => {
+                let path = Path::new(directory).join(format!("{:05}.png", number_of_frames));
+                *number_of_frames += 1;
+                write_output(&path.to_string_lossy(), &image.to_png()?)
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), String> {
+        match self {
+            FrameOutput::Gif(path, recorder) => write_output(&path, &recorder.finish()?),
+            FrameOutput::Png { .. } => Ok(()),
+        }
+    }
+}
+
+fn read_mask_file(path: &str) -> Result<Mask, String> {
+    let bytes = fs::read(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+    let mask = if path.to_lowercase().ends_with(".png") {
+        Image::from_png(&bytes).and_then(|image| read_mask(&image))
+    } else {
+        String::from_utf8(bytes)
+            .map_err(|_| "not a text file".to_owned())
+            .and_then(|text| Mask::from_ascii(&text))
+    };
+    mask.map_err(|e| format!("{}: {}", path, e))
+}
+
+// Grid the generation starts from, shaped by the mask if any
+fn empty_grid(options: &GenerateOptions) -> Grid {
+    match &options.mask {
+        Some(mask) => Grid::from_mask(mask),
+        None => Grid::new(options.number_of_cells_x, options.number_of_cells_y),
+    }
+}
+
+fn generate(options: &GenerateOptions) -> Result<(), String> {
+    let empty_grid = empty_grid(options);
+    let mut steps = if options.rooms.max_count > 0 {
+        options
+            .generator
+            .steps_with_rooms(empty_grid.clone(), &options.rooms, options.seed)
+    } else {
+        options.generator.steps(empty_grid.clone(), options.seed)
+    };
+    let mut events: Vec<_> = steps.by_ref().collect();
+    let mut grid = steps.into_grid();
+    let removed = sparsify(&mut grid, options.sparsify_passes);
+    events.extend(removed.into_iter().map(GenerationEvent::CellRemoved));
+    if options.braid > 0.0 {
+        let passages = braid(&mut grid, options.braid, options.seed);
+        events.extend(
+            passages
+                .into_iter()
+                .map(|(pos1, pos2)| GenerationEvent::PassageCarved(pos1, pos2)),
+        );
+    }
+    scatter_terrain(&mut grid, options.max_cost, options.seed);
+
+    if let Some(path) = &options.generation_recording {
+        let style = image_style(ImageStyle::viewer(), options)?;
+        let mut frames = FrameOutput::new(path, &grid, &style, options.frames_per_second)?;
+        record_generation(
+            &empty_grid,
+            &events,
+            &style,
+            Pace {
+                steps_per_second: GENERATION_STEPS_PER_SECOND,
+                frames_per_second: options.frames_per_second,
+            },
+            |image| frames.add_frame(image),
+        )?;
+        frames.finish()?;
+    }
+
+    if let Some(path) = &options.generation_trace_output {
+        let (number_of_cells_x, number_of_cells_y) = empty_grid.get_number_of_cells_x_y();
+        let void_cells = empty_grid.get_void_positions();
+        let trace = json::GenerationTrace {
+            generator: options.generator,
+            seed: options.seed,
+            number_of_cells_x,
+            number_of_cells_y,
+            void_cells,
+            events,
+        };
+        write_output(path, json::write_generation_trace(&trace).as_bytes())?;
+    }
+
+    write_maze(&grid, Some(options.seed), options)
+}
+
+fn read_maze_file(path: &str, cell_size: Option<u32>) -> Result<Grid, String> {
+    let bytes = fs::read(path).map_err(|e| format!("cannot read {}: {}", path, e))?;
+    let text = || String::from_utf8(bytes.clone()).map_err(|_| "not a text file".to_owned());
+    let extension = Path::new(path)
+        .extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase());
+    let grid = match extension.as_deref() {
+        Some("json") => text()
+            .and_then(|text| json::read_maze(&text))
+            .map(|file| file.grid),
+        Some("amaz") => binary::read(&bytes).map(|maze| maze.grid),
+        Some("edges") => text().and_then(|text| edges::parse(&text)),
+        Some("txt") => text().and_then(|text| ascii::parse(&text)),
+        Some("png") => {
+            let cell_size =
+                cell_size.ok_or_else(|| "missing --cell-size of the image".to_owned())?;
+            Image::from_png(&bytes).and_then(|image| read_maze(&image, cell_size))
+        }
+        _ => Err("unknown file type, expected .json, .amaz, .txt, .edges or .png".to_owned()),
+    };
+    grid.map_err(|e| format!("{}: {}", path, e))
+}
+
+fn import(args: &[String]) -> Result<(), String> {
+    let (path, args) = args
+        .split_first()
+        .ok_or_else(|| "missing file to import".to_owned())?;
+    if let Some(option) = args
+        .iter()
+        .step_by(2)
+        .find(|option| GENERATION_OPTIONS.contains(&option.as_str()))
+    {
+        return Err(format!("{} does not apply to import", option));
+    }
+    let options = parse_generate_options(args)?;
+    let grid = read_maze_file(path, options.cell_size)?;
+    write_maze(&grid, None, &options)
+}
+
+fn check(args: &[String]) -> Result<(), String> {
+    let (path, args) = args
+        .split_first()
+        .ok_or_else(|| "missing file to check".to_owned())?;
+    let mut cell_size: Option<u32> = None;
+    let mut args = args.iter();
+    while let Some(option) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value after {}", option))?;
+        match option.as_str() {
+            "--cell-size" => cell_size = Some(parse_cell_size(value)?),
+            _ => return Err(format!("{} does not apply to check", option)),
+        }
+    }
+    let grid = read_maze_file(path, cell_size)?;
+    check_perfect_maze(&grid).map_err(|e| format!("{}: not a perfect maze: {}", path, e))?;
+    println!("{}: perfect maze", path);
+    Ok(())
+}
+
+// Solves and writes the maze as asked by the output options
+fn write_maze(grid: &Grid, seed: Option<u64>, options: &GenerateOptions) -> Result<(), String> {
+    let (start_pos, end_pos) = options.endpoints.place(grid);
+    let trace = options
+        .solver
+        .map(|solver| solver.solve(start_pos, end_pos, grid));
+
+    if let Some(path) = &options.metrics_output {
+        let metrics = analyze(grid, start_pos, end_pos);
+        write_output(path, metrics.to_string().as_bytes())?;
+    }
+    let solution = trace.as_deref().map(erase_backtracking);
+
+    let distances = match options.heatmap_origin {
+        Some(HeatmapOrigin::Cell(origin)) if !grid.contains(origin) => {
+            return Err(format!(
+                "cell ({}, {}) is out of the maze",
+                origin.x, origin.y
+            ));
+        }
+        Some(HeatmapOrigin::Cell(origin)) => Some(distances_from(origin, grid)),
+        Some(HeatmapOrigin::Start) => Some(distances_from(start_pos, grid)),
+        None => None,
+    };
+
+    if let (Some(path), Some(trace)) = (&options.solving_recording, &trace) {
+        let style = image_style(ImageStyle::viewer(), options)?;
+        let mut frames = FrameOutput::new(path, grid, &style, options.frames_per_second)?;
+        record_solving(
+            grid,
+            trace,
+            start_pos,
+            end_pos,
+            &style,
+            Pace {
+                steps_per_second: SOLVING_STEPS_PER_SECOND,
+                frames_per_second: options.frames_per_second,
+            },
+            |image| frames.add_frame(image),
+        )?;
+        frames.finish()?;
+    }
+
+    let style = image_style(options.format.default_style(), options)?;
+    let write_options = WriteOptions {
+        solution: solution.as_deref(),
+        trace: trace.as_deref(),
+        colors: options
+            .colors
+            .unwrap_or(options.output.is_none() && std::io::stdout().is_terminal()),
+        seed,
+        start: Some(start_pos),
+        goal: Some(end_pos),
+        distances: distances.as_ref(),
+        style: Some(style),
+    };
+    // the other formats read back store the costs, the images only tint them
+    if options.format == Format::Png && grid.get_max_cost() > 1 {
+        eprintln!(
+            "warning: the costs of the cells are only drawn in a png, importing it sets them back to 1"
+        );
+    }
+    let bytes = options.format.write(grid, &write_options)?;
+    match &options.output {
+        Some(path) => write_output(path, &bytes),
+        None => std::io::stdout()
+            .write_all(&bytes)
+            .map_err(|e| format!("cannot write to stdout: {}", e)),
+    }
+}
+
+fn uniformity(args: &[String]) -> Result<(), String> {
+    let mut generator = Generator::Wilson;
+    let (mut number_of_cells_x, mut number_of_cells_y) = (3, 3);
+    let mut seed: u64 = rand::random();
+    let mut samples: Option<usize> = None;
+    let mut mask: Option<Mask> = None;
+
+    let mut args = args.iter();
+    while let Some(option) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("missing value after {}", option))?;
+        match option.as_str() {
+            "--generator" => generator = parse_generator(value)?,
+            "--width" => number_of_cells_x = parse_number_of_cells(value)?,
+            "--height" => number_of_cells_y = parse_number_of_cells(value)?,
+            "--mask" => mask = Some(read_mask_file(value)?),
+            "--seed" => {
+                seed = value
+                    .parse()
+                    .map_err(|_| format!("invalid seed '{}'", value))?
+            }
+            "--samples" => {
+                samples = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid number of samples '{}'", value))?,
+                )
+            }
+            _ => return Err(format!("unknown option {}", option)),
+        }
+    }
+
+    let empty_grid = match mask {
+        Some(mask) => Grid::from_mask(&mask),
+        None => Grid::new(number_of_cells_x, number_of_cells_y),
+    };
+    let report = check_uniformity(generator, &empty_grid, samples, seed)?;
+    print!("{}", report);
+    Ok(())
+}
+
+/// Runs the command described by the arguments (without the program name)
+pub fn run(args: &[String]) -> Result<(), String> {
+    match args.first().map(String::as_str) {
+        Some("generate") => generate(&parse_generate_options(&args[1..])?),
+        Some("import") => import(&args[1..]),
+        Some("check") => check(&args[1..]),
+        Some("uniformity") => uniformity(&args[1..]),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(command) => Err(format!("unknown command '{}'\n\n{}", command, USAGE)),
+        None => Err(USAGE.to_owned()),
+    }
+}
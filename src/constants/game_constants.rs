@@ -2,3 +2,7 @@ pub const NUMBER_OF_CELLS_X: u16 = 20;
 pub const NUMBER_OF_CELLS_Y: u16 = 20;
 
 pub const REFRESH_RATE_MS: usize = 100;
+
+// bounds of the grid dimensions selectable in the menu
+pub const MIN_NUMBER_OF_CELLS: u16 = 2;
+pub const MAX_NUMBER_OF_CELLS: u16 = 100;
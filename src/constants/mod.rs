@@ -1,2 +1,2 @@
 pub mod game_constants;
-pub mod gui_constants;
\ No newline at end of file
+pub mod render_constants;
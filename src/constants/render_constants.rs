@@ -0,0 +1,16 @@
+// The viewer's look, for the image formats which can't depend on macroquad
+
+// part of a cell taken by each of its walls
+pub const WALL_RATIO: f64 = 0.05;
+
+pub const BACKGROUND_RGB: [u8; 3] = [0, 0, 0];
+pub const CELL_RGB: [u8; 3] = [130, 130, 130];
+pub const PATH_RGB: [u8; 3] = [0, 228, 48];
+pub const LEP_PATH_RGB: [u8; 3] = [200, 122, 255];
+pub const ACCENT_RGB: [u8; 3] = [230, 41, 55];
+pub const START_RGB: [u8; 3] = [253, 249, 0];
+// ends of the distance heatmap
+pub const HEATMAP_NEAR_RGB: [u8; 3] = [0, 121, 241];
+pub const HEATMAP_FAR_RGB: [u8; 3] = [230, 41, 55];
+// tint of the most expensive cells to walk through
+pub const COSTLY_RGB: [u8; 3] = [139, 69, 19];
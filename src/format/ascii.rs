@@ -0,0 +1,314 @@
+use crate::format::remove_void_cells;
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+
+const SOLUTION_MARK: &str = "**";
+const VOID_MARK: &str = "##";
+
+// whether the solution goes from one cell to the other
+fn is_solution_step(solution: &[Position], pos1: Position, pos2: Position) -> bool {
+    solution
+        .windows(2)
+        .any(|step| (step[0] == pos1 && step[1] == pos2) || (step[0] == pos2 && step[1] == pos1))
+}
+
+/// Draws the maze with `+`, `-` and `|`, each cell being two characters wide.
+/// Void cells, out of the maze, are filled with `#` and the solution, if
+/// any, is drawn with `*`. The cells which don't cost 1 to walk into are
+/// listed below, like in the edges format:
+///
+/// ```text
+/// +--+--+--+
+/// |**|     |
+/// +**+  +--+
+/// |********|
+/// +--+--+**+
+/// |      **|
+/// +--+--+--+
+/// cost 1 1 3
+/// ```
+pub fn write(grid: &Grid, solution: Option<&[Position]>) -> String {
+    let (number_of_cells_x, number_of_cells_y) = grid.get_number_of_cells_x_y();
+    let solution = solution.unwrap_or(&[]);
+
+    let mut text = "+--".repeat(number_of_cells_x as usize) + "+\n";
+    for y in 0..number_of_cells_y as usize {
+        let mut cells_line = String::from("|");
+        let mut walls_line = String::from("+");
+        for x in 0..number_of_cells_x as usize {
+            let pos = Position { x, y };
+            cells_line += if solution.contains(&pos) {
+                SOLUTION_MARK
+            } else if !grid.contains(pos) {
+                VOID_MARK
+            } else {
+                "  "
+            };
+            let right_pos = Position { x: x + 1, y };
+            cells_line += if is_solution_step(solution, pos, right_pos) {
+                "*"
+            } else if grid.are_neighbors(pos, right_pos) {
+                " "
+            } else {
+                "|"
+            };
+            let bottom_pos = Position { x, y: y + 1 };
+            walls_line += if is_solution_step(solution, pos, bottom_pos) {
+                "**+"
+            } else if grid.are_neighbors(pos, bottom_pos) {
+                "  +"
+            } else {
+                "--+"
+            };
+        }
+        text += &cells_line;
+        text += "\n";
+        text += &walls_line;
+        text += "\n";
+    }
+    for pos in grid.get_cells_positions() {
+        if grid.get_cost(pos) != 1 {
+            text += &format!("cost {} {} {}\n", pos.x, pos.y, grid.get_cost(pos));
+        }
+    }
+    text
+}
+
+// `cost <x> <y> <cost>` line below the maze
+fn parse_cost(line: &str, grid: &mut Grid) -> Result<(), String> {
+    let invalid = || format!("invalid cost line '{}', expected cost <x> <y> <cost>", line);
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [_, x, y, cost] = fields[..] else {
+        return Err(invalid());
+    };
+    let pos = Position {
+        x: x.parse().map_err(|_| invalid())?,
+        y: y.parse().map_err(|_| invalid())?,
+    };
+    let cost = match cost.parse::<u32>() {
+        Ok(cost) if cost > 0 => cost,
+        _ => return Err(invalid()),
+    };
+    if !grid.contains(pos) {
+        return Err(format!(
+            "cost of cell ({}, {}) out of the maze",
+            pos.x, pos.y
+        ));
+    }
+    grid.set_cost(pos, cost);
+    Ok(())
+}
+
+fn char_at(lines: &[Vec<char>], row: usize, column: usize) -> char {
+    lines[row].get(column).copied().unwrap_or(' ')
+}
+
+fn expect(lines: &[Vec<char>], row: usize, column: usize, allowed: &str) -> Result<char, String> {
+    let c = char_at(lines, row, column);
+    if allowed.contains(c) {
+        Ok(c)
+    } else {
+        Err(format!(
+            "line {}, column {}: expected one of '{}', found '{}'",
+            row + 1,
+            column + 1,
+            allowed,
+            c
+        ))
+    }
+}
+
+/// Reads back a maze drawn by `write`. Solution marks are ignored, cells
+/// filled with `#` are void.
+pub fn parse(text: &str) -> Result<Grid, String> {
+    let mut lines: Vec<&str> = text
+        .lines()
+        .map(|line| line.trim_end())
+        .filter(|line| !line.is_empty())
+        .collect();
+    let first_cost = lines
+        .iter()
+        .position(|line| line.starts_with("cost"))
+        .unwrap_or(lines.len());
+    let cost_lines = lines.split_off(first_cost);
+    let lines: Vec<Vec<char>> = lines.iter().map(|line| line.chars().collect()).collect();
+
+    let Some(first_line) = lines.first() else {
+        return Err("empty maze".to_owned());
+    };
+    if lines.len() < 3 || lines.len().is_multiple_of(2) {
+        return Err(format!(
+            "a maze has an odd number of lines and at least 3, found {}",
+            lines.len()
+        ));
+    }
+    if first_line.len() < 4 || first_line.len() % 3 != 1 {
+        return Err(format!(
+            "line 1: a row of n cells is 3 * n + 1 characters long, found {}",
+            first_line.len()
+        ));
+    }
+
+    let number_of_cells_x =
+        u16::try_from((first_line.len() - 1) / 3).map_err(|_| "the maze is too wide".to_owned())?;
+    let number_of_cells_y =
+        u16::try_from((lines.len() - 1) / 2).map_err(|_| "the maze is too high".to_owned())?;
+    let mut grid = Grid::new(number_of_cells_x, number_of_cells_y);
+    let mut void_cells: Vec<Position> = Vec::new();
+
+    for y in 0..number_of_cells_y as usize {
+        let cells_row = 2 * y + 1;
+        let walls_row = 2 * y + 2;
+        expect(&lines, cells_row, 0, "|")?;
+        expect(&lines, walls_row, 0, "+")?;
+
+        for x in 0..number_of_cells_x as usize {
+            let column = 3 * x;
+            let last_column = x + 1 == number_of_cells_x as usize;
+            let last_row = y + 1 == number_of_cells_y as usize;
+            if y == 0 {
+                expect(&lines, 0, column + 1, "-")?;
+                expect(&lines, 0, column + 2, "-")?;
+                expect(&lines, 0, column + 3, "+")?;
+            }
+
+            let left_half = expect(&lines, cells_row, column + 1, " *#")?;
+            let right_half = expect(&lines, cells_row, column + 2, " *#")?;
+            if (left_half == '#') != (right_half == '#') {
+                return Err(format!(
+                    "line {}, column {}: half void cell",
+                    cells_row + 1,
+                    column + 2
+                ));
+            }
+            if left_half == '#' {
+                void_cells.push(Position { x, y });
+            }
+
+            let right_wall = if last_column { "|" } else { "| *" };
+            if expect(&lines, cells_row, column + 3, right_wall)? != '|' {
+                grid.try_add_neighbor(Position { x, y }, Position { x: x + 1, y })?;
+            }
+
+            let bottom_wall = if last_row { "-" } else { "- *" };
+            let left_half = expect(&lines, walls_row, column + 1, bottom_wall)?;
+            let right_half = expect(&lines, walls_row, column + 2, bottom_wall)?;
+            if left_half != right_half {
+                return Err(format!(
+                    "line {}, column {}: half open wall",
+                    walls_row + 1,
+                    column + 2
+                ));
+            }
+            if left_half != '-' {
+                grid.try_add_neighbor(Position { x, y }, Position { x, y: y + 1 })?;
+            }
+            expect(&lines, walls_row, column + 3, "+")?;
+        }
+    }
+    remove_void_cells(&mut grid, &void_cells)?;
+    for line in cost_lines {
+        parse_cost(line, &mut grid)?;
+    }
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::test_mazes::{assert_same_maze, read_error, sample_maze};
+    use crate::mouse::shortest_path;
+
+    const MAZE: &str = "\
++--+--+--+
+|     |##|
++  +  +--+
+|  |     |
++--+--+--+
+";
+
+    #[test]
+    fn round_trips() {
+        let grid = sample_maze();
+        assert_same_maze(&grid, &parse(&write(&grid, None)).unwrap());
+    }
+
+    #[test]
+    fn ignores_the_solution() {
+        let grid = sample_maze();
+        let positions = grid.get_cells_positions();
+        let solution = shortest_path(positions[0], positions[positions.len() - 1], &grid);
+        let text = write(&grid, Some(&solution));
+        assert!(text.contains(SOLUTION_MARK));
+        assert_same_maze(&grid, &parse(&text).unwrap());
+    }
+
+    #[test]
+    fn reads_the_passages() {
+        let grid = parse(MAZE).unwrap();
+        assert_eq!(grid.get_number_of_cells_x_y(), (3, 2));
+        assert!(grid.are_neighbors(Position { x: 1, y: 0 }, Position { x: 1, y: 1 }));
+        assert!(grid.are_neighbors(Position { x: 1, y: 1 }, Position { x: 2, y: 1 }));
+        assert!(!grid.are_neighbors(Position { x: 1, y: 0 }, Position { x: 2, y: 0 }));
+    }
+
+    #[test]
+    fn reads_void_cells() {
+        let grid = parse(MAZE).unwrap();
+        assert!(!grid.contains(Position { x: 2, y: 0 }));
+        assert_eq!(grid.get_cells_positions().len(), 5);
+    }
+
+    #[test]
+    fn rejects_a_void_cell_with_a_passage() {
+        let text = MAZE.replace("|     |##|", "|   ##|##|");
+        assert_eq!(read_error(parse(&text)), "void cell (1, 0) has a passage");
+    }
+
+    #[test]
+    fn rejects_a_ragged_row() {
+        let text = MAZE.replace("|  |     |", "|  |     ");
+        assert_eq!(
+            read_error(parse(&text)),
+            "line 4, column 10: expected one of '|', found ' '"
+        );
+    }
+
+    #[test]
+    fn rejects_a_wrong_number_of_lines() {
+        let text = MAZE.replacen("+--+--+--+\n", "", 1);
+        assert_eq!(
+            read_error(parse(&text)),
+            "a maze has an odd number of lines and at least 3, found 4"
+        );
+    }
+
+    #[test]
+    fn rejects_a_wrong_width() {
+        let text = MAZE.replacen("+--+--+--+", "+--+--+--+-", 1);
+        assert_eq!(
+            read_error(parse(&text)),
+            "line 1: a row of n cells is 3 * n + 1 characters long, found 11"
+        );
+    }
+
+    #[test]
+    fn rejects_a_half_open_wall() {
+        let text = MAZE.replace("+  +  +--+", "+  + -+--+");
+        assert_eq!(read_error(parse(&text)), "line 3, column 5: half open wall");
+    }
+
+    #[test]
+    fn rejects_an_invalid_cost() {
+        let text = MAZE.to_owned() + "cost 0 0 0\n";
+        assert_eq!(
+            read_error(parse(&text)),
+            "invalid cost line 'cost 0 0 0', expected cost <x> <y> <cost>"
+        );
+        let text = MAZE.to_owned() + "cost 2 0 4\n";
+        assert_eq!(
+            read_error(parse(&text)),
+            "cost of cell (2, 0) out of the maze"
+        );
+    }
+}
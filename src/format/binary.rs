@@ -0,0 +1,423 @@
+//! Compact binary format, for storing large amounts of mazes.
+//!
+//! All integers are little endian:
+//!
+//! | offset | size | content                                        |
+//! |--------|------|------------------------------------------------|
+//! | 0      | 4    | magic `AMAZ`                                   |
+//! | 4      | 1    | version, currently 1                           |
+//! | 5      | 1    | topology, 0 for orthogonal                     |
+//! | 6      | 1    | flags, bit 0 set when the seed is known, bit 1 |
+//! |        |      | when the costs follow                          |
+//! | 7      | 1    | reserved, 0                                    |
+//! | 8      | 2    | number of cells on the x axis                  |
+//! | 10     | 2    | number of cells on the y axis                  |
+//! | 12     | 8    | seed, 0 when unknown                           |
+//! | 20     |      | walls of every cell, row by row, 4 bits each   |
+//! |        |      | void cells, row by row, 1 bit each             |
+//! |        | 4    | number of costs, with flag bit 1 only          |
+//! |        | 8    | each cost: x and y on 2 bytes, cost on 4       |
+//!
+//! Two cells are packed per byte, the first one in the low bits. The walls
+//! of a cell are `1` up, `2` right, `4` down and `8` left. The bit of a void
+//! cell, out of the maze, is set, eight cells being packed per byte from the
+//! low bit, and its four walls are set too. The costs of the cells are only
+//! listed for the ones which don't cost 1.
+
+use crate::format::remove_void_cells;
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+
+pub const MAGIC: &[u8; 4] = b"AMAZ";
+pub const VERSION: u8 = 1;
+pub const TOPOLOGY_ORTHOGONAL: u8 = 0;
+
+const HEADER_SIZE: usize = 20;
+const FLAG_SEED: u8 = 1;
+const FLAG_COSTS: u8 = 2;
+const COST_SIZE: usize = 8;
+
+const WALL_UP: u8 = 1;
+const WALL_RIGHT: u8 = 2;
+const WALL_DOWN: u8 = 4;
+const WALL_LEFT: u8 = 8;
+
+/// A maze read back from the binary format
+pub struct BinaryMaze {
+    pub grid: Grid,
+    pub seed: Option<u64>,
+}
+
+fn walls_of(grid: &Grid, pos: Position) -> u8 {
+    let open = |other: Option<Position>| other.is_some_and(|other| grid.are_neighbors(pos, other));
+    let mut walls = 0;
+    if !open(pos.y.checked_sub(1).map(|y| Position { x: pos.x, y })) {
+        walls |= WALL_UP;
+    }
+    if !open(Some(Position {
+        x: pos.x + 1,
+        y: pos.y,
+    })) {
+        walls |= WALL_RIGHT;
+    }
+    if !open(Some(Position {
+        x: pos.x,
+        y: pos.y + 1,
+    })) {
+        walls |= WALL_DOWN;
+    }
+    if !open(pos.x.checked_sub(1).map(|x| Position { x, y: pos.y })) {
+        walls |= WALL_LEFT;
+    }
+    walls
+}
+
+pub fn write(grid: &Grid, seed: Option<u64>) -> Vec<u8> {
+    let (number_of_cells_x, number_of_cells_y) = grid.get_number_of_cells_x_y();
+
+    let number_of_cells = number_of_cells_x as usize * number_of_cells_y as usize;
+    let mut bytes: Vec<u8> =
+        Vec::with_capacity(HEADER_SIZE + number_of_cells.div_ceil(2) + number_of_cells.div_ceil(8));
+    bytes.extend_from_slice(MAGIC);
+    bytes.push(VERSION);
+    bytes.push(TOPOLOGY_ORTHOGONAL);
+    let costly: Vec<Position> = grid
+        .get_cells_positions()
+        .into_iter()
+        .filter(|pos| grid.get_cost(*pos) != 1)
+        .collect();
+    let mut flags = 0;
+    if seed.is_some() {
+        flags |= FLAG_SEED;
+    }
+    if !costly.is_empty() {
+        flags |= FLAG_COSTS;
+    }
+    bytes.push(flags);
+    bytes.push(0);
+    bytes.extend_from_slice(&number_of_cells_x.to_le_bytes());
+    bytes.extend_from_slice(&number_of_cells_y.to_le_bytes());
+    bytes.extend_from_slice(&seed.unwrap_or(0).to_le_bytes());
+
+    // void cells included, every position of the grid has its 4 bits
+    let positions: Vec<Position> = (0..number_of_cells_y as usize)
+        .flat_map(|y| (0..number_of_cells_x as usize).map(move |x| Position { x, y }))
+        .collect();
+    for pair in positions.chunks(2) {
+        let low = walls_of(grid, pair[0]);
+        let high = pair.get(1).map_or(0, |pos| walls_of(grid, *pos));
+        bytes.push(low | (high << 4));
+    }
+    for octet in positions.chunks(8) {
+        let void_bits = octet
+            .iter()
+            .enumerate()
+            .filter(|(_, pos)| !grid.contains(**pos))
+            .fold(0, |bits, (i, _)| bits | 1 << i);
+        bytes.push(void_bits);
+    }
+    if !costly.is_empty() {
+        bytes.extend_from_slice(&(costly.len() as u32).to_le_bytes());
+        for pos in costly {
+            bytes.extend_from_slice(&(pos.x as u16).to_le_bytes());
+            bytes.extend_from_slice(&(pos.y as u16).to_le_bytes());
+            bytes.extend_from_slice(&grid.get_cost(pos).to_le_bytes());
+        }
+    }
+    bytes
+}
+
+pub fn read(bytes: &[u8]) -> Result<BinaryMaze, String> {
+    if bytes.len() < HEADER_SIZE {
+        return Err(format!(
+            "truncated header: {} bytes instead of {}",
+            bytes.len(),
+            HEADER_SIZE
+        ));
+    }
+    if &bytes[0..4] != MAGIC {
+        return Err("not a maze file: bad magic number".to_owned());
+    }
+    let version = bytes[4];
+    if version != VERSION {
+        return Err(format!(
+            "unsupported version {}, expected {}",
+            version, VERSION
+        ));
+    }
+    if bytes[5] != TOPOLOGY_ORTHOGONAL {
+        return Err(format!("unsupported topology {}", bytes[5]));
+    }
+    let flags = bytes[6];
+    if flags & !(FLAG_SEED | FLAG_COSTS) != 0 {
+        return Err(format!("unknown flags {:#04x}", flags));
+    }
+    if bytes[7] != 0 {
+        return Err(format!("reserved byte set to {}", bytes[7]));
+    }
+
+    let number_of_cells_x = u16::from_le_bytes([bytes[8], bytes[9]]);
+    let number_of_cells_y = u16::from_le_bytes([bytes[10], bytes[11]]);
+    let seed = u64::from_le_bytes(bytes[12..20].try_into().unwrap());
+    let seed = if flags & FLAG_SEED != 0 {
+        Some(seed)
+    } else {
+        None
+    };
+
+    let number_of_cells = number_of_cells_x as usize * number_of_cells_y as usize;
+    let walls_size = number_of_cells.div_ceil(2);
+    let void_size = number_of_cells.div_ceil(8);
+    let cells_size = HEADER_SIZE + walls_size + void_size;
+    let mut expected_size = cells_size;
+    if flags & FLAG_COSTS != 0 {
+        let number_of_costs = bytes
+            .get(cells_size..cells_size + 4)
+            .map(|count| u32::from_le_bytes(count.try_into().unwrap()) as usize)
+            .ok_or_else(|| format!("truncated file: no number of costs at {}", cells_size))?;
+        expected_size += 4 + number_of_costs * COST_SIZE;
+    }
+    if bytes.len() != expected_size {
+        let problem = if bytes.len() < expected_size {
+            "truncated"
+        } else {
+            "trailing data in"
+        };
+        return Err(format!(
+            "{} file: {} bytes instead of {} for {}x{} cells",
+            problem,
+            bytes.len(),
+            expected_size,
+            number_of_cells_x,
+            number_of_cells_y
+        ));
+    }
+
+    let walls_at = |x: usize, y: usize| -> u8 {
+        let index = y * number_of_cells_x as usize + x;
+        (bytes[HEADER_SIZE + index / 2] >> (4 * (index % 2))) & 0xf
+    };
+
+    let mut grid = Grid::new(number_of_cells_x, number_of_cells_y);
+    for y in 0..number_of_cells_y as usize {
+        for x in 0..number_of_cells_x as usize {
+            let walls = walls_at(x, y);
+            let last_column = x + 1 == number_of_cells_x as usize;
+            let last_row = y + 1 == number_of_cells_y as usize;
+
+            if (y == 0 && walls & WALL_UP == 0)
+                || (x == 0 && walls & WALL_LEFT == 0)
+                || (last_column && walls & WALL_RIGHT == 0)
+                || (last_row && walls & WALL_DOWN == 0)
+            {
+                return Err(format!("cell ({}, {}) opens outside of the maze", x, y));
+            }
+
+            if !last_column {
+                let right_walls = walls_at(x + 1, y);
+                if (walls & WALL_RIGHT == 0) != (right_walls & WALL_LEFT == 0) {
+                    return Err(format!(
+                        "cells ({}, {}) and ({}, {}) disagree on their common wall",
+                        x,
+                        y,
+                        x + 1,
+                        y
+                    ));
+                }
+                if walls & WALL_RIGHT == 0 {
+                    grid.try_add_neighbor(Position { x, y }, Position { x: x + 1, y })?;
+                }
+            }
+            if !last_row {
+                let bottom_walls = walls_at(x, y + 1);
+                if (walls & WALL_DOWN == 0) != (bottom_walls & WALL_UP == 0) {
+                    return Err(format!(
+                        "cells ({}, {}) and ({}, {}) disagree on their common wall",
+                        x,
+                        y,
+                        x,
+                        y + 1
+                    ));
+                }
+                if walls & WALL_DOWN == 0 {
+                    grid.try_add_neighbor(Position { x, y }, Position { x, y: y + 1 })?;
+                }
+            }
+        }
+    }
+
+    // the padding of an odd number of cells must be empty
+    if !number_of_cells.is_multiple_of(2) && bytes[HEADER_SIZE + walls_size - 1] >> 4 != 0 {
+        return Err("non zero padding after the last cell".to_owned());
+    }
+
+    let void_bits = &bytes[HEADER_SIZE + walls_size..cells_size];
+    if !number_of_cells.is_multiple_of(8)
+        && void_bits
+            .last()
+            .is_some_and(|last| last >> (number_of_cells % 8) != 0)
+    {
+        return Err("non zero padding after the last void cell".to_owned());
+    }
+    let void_cells: Vec<Position> = (0..number_of_cells)
+        .filter(|index| {
+            void_bits
+                .get(index / 8)
+                .is_some_and(|bits| bits >> (index % 8) & 1 != 0)
+        })
+        .map(|index| Position {
+            x: index % number_of_cells_x as usize,
+            y: index / number_of_cells_x as usize,
+        })
+        .collect();
+    remove_void_cells(&mut grid, &void_cells)?;
+
+    if flags & FLAG_COSTS != 0 {
+        for entry in bytes[cells_size + 4..].chunks(COST_SIZE) {
+            let pos = Position {
+                x: u16::from_le_bytes([entry[0], entry[1]]) as usize,
+                y: u16::from_le_bytes([entry[2], entry[3]]) as usize,
+            };
+            let cost = u32::from_le_bytes(entry[4..8].try_into().unwrap());
+            if !grid.contains(pos) {
+                return Err(format!(
+                    "cost of cell ({}, {}) out of the maze",
+                    pos.x, pos.y
+                ));
+            }
+            if cost == 0 {
+                return Err(format!("invalid cost 0 of cell ({}, {})", pos.x, pos.y));
+            }
+            grid.set_cost(pos, cost);
+        }
+    }
+
+    Ok(BinaryMaze { grid, seed })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::test_mazes::{assert_same_maze, read_error, sample_maze};
+
+    // 2x1 maze with a passage between its two cells
+    fn two_cells() -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&[VERSION, TOPOLOGY_ORTHOGONAL, 0, 0, 2, 0, 1, 0]);
+        bytes.extend_from_slice(&[0; 8]);
+        // walls up, down and left, then up, right and down
+        bytes.push(13 | (7 << 4));
+        // no void cell
+        bytes.push(0);
+        bytes
+    }
+
+    #[test]
+    fn round_trips() {
+        let grid = sample_maze();
+        let maze = read(&write(&grid, Some(42))).unwrap();
+        assert_same_maze(&grid, &maze.grid);
+        assert_eq!(maze.seed, Some(42));
+
+        let maze = read(&write(&grid, None)).unwrap();
+        assert_same_maze(&grid, &maze.grid);
+        assert_eq!(maze.seed, None);
+    }
+
+    #[test]
+    fn reads_a_handmade_file() {
+        let maze = read(&two_cells()).unwrap();
+        assert!(
+            maze.grid
+                .are_neighbors(Position { x: 0, y: 0 }, Position { x: 1, y: 0 })
+        );
+        assert_eq!(write(&maze.grid, None), two_cells());
+    }
+
+    #[test]
+    fn rejects_a_truncated_header() {
+        assert_eq!(
+            read_error(read(&two_cells()[..10])),
+            "truncated header: 10 bytes instead of 20"
+        );
+    }
+
+    #[test]
+    fn rejects_a_bad_magic_number() {
+        let mut bytes = two_cells();
+        bytes[0] = b'X';
+        assert_eq!(
+            read_error(read(&bytes)),
+            "not a maze file: bad magic number"
+        );
+    }
+
+    #[test]
+    fn rejects_an_unknown_version() {
+        let mut bytes = two_cells();
+        bytes[4] = 9;
+        assert_eq!(
+            read_error(read(&bytes)),
+            "unsupported version 9, expected 1"
+        );
+    }
+
+    #[test]
+    fn rejects_a_wrong_size() {
+        let mut bytes = two_cells();
+        bytes.push(0);
+        assert_eq!(
+            read_error(read(&bytes)),
+            "trailing data in file: 23 bytes instead of 22 for 2x1 cells"
+        );
+        bytes.truncate(21);
+        assert_eq!(
+            read_error(read(&bytes)),
+            "truncated file: 21 bytes instead of 22 for 2x1 cells"
+        );
+    }
+
+    #[test]
+    fn rejects_walls_which_disagree() {
+        let mut bytes = two_cells();
+        bytes[HEADER_SIZE] = 13 | (15 << 4);
+        assert_eq!(
+            read_error(read(&bytes)),
+            "cells (0, 0) and (1, 0) disagree on their common wall"
+        );
+    }
+
+    #[test]
+    fn rejects_a_cell_opening_outside() {
+        let mut bytes = two_cells();
+        bytes[HEADER_SIZE] = 5 | (7 << 4);
+        assert_eq!(
+            read_error(read(&bytes)),
+            "cell (0, 0) opens outside of the maze"
+        );
+    }
+
+    #[test]
+    fn rejects_a_void_cell_with_a_passage() {
+        let mut bytes = two_cells();
+        bytes[HEADER_SIZE + 1] = 1;
+        assert_eq!(read_error(read(&bytes)), "void cell (0, 0) has a passage");
+    }
+
+    #[test]
+    fn rejects_a_cost_out_of_the_maze() {
+        let mut bytes = two_cells();
+        bytes[6] = FLAG_COSTS;
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&[2, 0, 0, 0, 5, 0, 0, 0]);
+        assert_eq!(
+            read_error(read(&bytes)),
+            "cost of cell (2, 0) out of the maze"
+        );
+        bytes.truncate(22);
+        assert_eq!(
+            read_error(read(&bytes)),
+            "truncated file: no number of costs at 22"
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+
+/// Plain list of the passages, one per line:
+///
+/// ```text
+/// size 3 2
+/// void 2 0
+/// passage 0 0 1 0
+/// passage 1 0 1 1
+/// cost 1 1 3
+/// solution 0 0 1 0 1 1
+/// ```
+///
+/// Only the positions out of the maze have a void line, and only the cells
+/// which don't cost 1 to walk into have a cost line.
+pub fn write(grid: &Grid, solution: Option<&[Position]>) -> String {
+    let (number_of_cells_x, number_of_cells_y) = grid.get_number_of_cells_x_y();
+    let mut text = format!("size {} {}\n", number_of_cells_x, number_of_cells_y);
+
+    for pos in grid.get_void_positions() {
+        text += &format!("void {} {}\n", pos.x, pos.y);
+    }
+
+    for pos in grid.get_cells_positions() {
+        for neighbor in grid.get_neighbors_of_pos(pos) {
+            // each passage is listed once, from its top left cell
+            if (neighbor.y, neighbor.x) > (pos.y, pos.x) {
+                text += &format!(
+                    "passage {} {} {} {}\n",
+                    pos.x, pos.y, neighbor.x, neighbor.y
+                );
+            }
+        }
+    }
+
+    for pos in grid.get_cells_positions() {
+        if grid.get_cost(pos) != 1 {
+            text += &format!("cost {} {} {}\n", pos.x, pos.y, grid.get_cost(pos));
+        }
+    }
+
+    if let Some(solution) = solution {
+        text += "solution";
+        for pos in solution {
+            text += &format!(" {} {}", pos.x, pos.y);
+        }
+        text += "\n";
+    }
+    text
+}
+
+// Numbers after the keyword of a line, `count` of them or any even number if
+// `count` is 0
+fn parse_numbers(line: &str, count: usize) -> Result<Vec<usize>, String> {
+    let numbers: Vec<usize> = line
+        .split_whitespace()
+        .skip(1)
+        .map(|field| field.parse::<usize>())
+        .collect::<Result<_, _>>()
+        .map_err(|_| format!("invalid line '{}'", line))?;
+    let expected = match count {
+        0 => numbers.len().is_multiple_of(2),
+        _ => numbers.len() == count,
+    };
+    if !expected {
+        return Err(format!("invalid line '{}'", line));
+    }
+    Ok(numbers)
+}
+
+fn read_cell(grid: &Grid, x: usize, y: usize) -> Result<Position, String> {
+    let pos = Position { x, y };
+    if !grid.contains(pos) {
+        return Err(format!("cell ({}, {}) is out of the maze", x, y));
+    }
+    Ok(pos)
+}
+
+// Applies a line other than the size and the void cells to the grid
+fn parse_line(line: &str, grid: &mut Grid) -> Result<(), String> {
+    match line.split_whitespace().next() {
+        Some("void") => Ok(()),
+        Some("passage") => {
+            let numbers = parse_numbers(line, 4)?;
+            let from = read_cell(grid, numbers[0], numbers[1])?;
+            let to = read_cell(grid, numbers[2], numbers[3])?;
+            grid.try_add_neighbor(from, to)
+        }
+        Some("cost") => {
+            let numbers = parse_numbers(line, 3)?;
+            let pos = read_cell(grid, numbers[0], numbers[1])?;
+            let cost = u32::try_from(numbers[2])
+                .ok()
+                .filter(|cost| *cost > 0)
+                .ok_or_else(|| {
+                    format!("invalid cost {} of cell ({}, {})", numbers[2], pos.x, pos.y)
+                })?;
+            grid.set_cost(pos, cost);
+            Ok(())
+        }
+        // only checked, like the solution marks of the ascii format
+        Some("solution") => {
+            for cell in parse_numbers(line, 0)?.chunks_exact(2) {
+                read_cell(grid, cell[0], cell[1])?;
+            }
+            Ok(())
+        }
+        _ => Err(format!("unknown line '{}'", line)),
+    }
+}
+
+/// Reads back a maze written by `write`, without its solution
+pub fn parse(text: &str) -> Result<Grid, String> {
+    let lines: Vec<(usize, &str)> = text
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .collect();
+    let with_line = |number: usize| move |error: String| format!("line {}: {}", number, error);
+
+    let (first_number, first) = lines.first().copied().ok_or("empty file")?;
+    if !first.starts_with("size") {
+        return Err(format!(
+            "line {}: expected size <width> <height>",
+            first_number
+        ));
+    }
+    let size = parse_numbers(first, 2).map_err(with_line(first_number))?;
+    let dimension = |value: usize| {
+        u16::try_from(value)
+            .ok()
+            .filter(|value| *value > 0)
+            .ok_or_else(|| format!("line {}: invalid size '{}'", first_number, first))
+    };
+    let mut grid = Grid::new(dimension(size[0])?, dimension(size[1])?);
+
+    // void cells go first so that passages to them are out of the maze
+    for (number, line) in &lines[1..] {
+        if line.starts_with("void") {
+            let numbers = parse_numbers(line, 2).map_err(with_line(*number))?;
+            let pos = read_cell(&grid, numbers[0], numbers[1]).map_err(with_line(*number))?;
+            grid.remove_cell(pos);
+        }
+    }
+    for (number, line) in &lines[1..] {
+        parse_line(line, &mut grid).map_err(with_line(*number))?;
+    }
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::test_mazes::{assert_same_maze, read_error, sample_maze};
+    use crate::mouse::shortest_path;
+
+    #[test]
+    fn round_trips() {
+        let grid = sample_maze();
+        let positions = grid.get_cells_positions();
+        let solution = shortest_path(positions[0], positions[positions.len() - 1], &grid);
+        let text = write(&grid, Some(&solution));
+        assert!(text.contains("\nvoid 5 0\n"));
+        assert_same_maze(&grid, &parse(&text).unwrap());
+    }
+
+    #[test]
+    fn rejects_a_passage_into_a_void_cell() {
+        let text = "size 2 1\nvoid 1 0\npassage 0 0 1 0\n";
+        assert_eq!(
+            read_error(parse(text)),
+            "line 3: cell (1, 0) is out of the maze"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        assert_eq!(
+            read_error(parse("passage 0 0 1 0\n")),
+            "line 1: expected size <width> <height>"
+        );
+        assert_eq!(
+            read_error(parse("size 2 1\npassage 0 0 1\n")),
+            "line 2: invalid line 'passage 0 0 1'"
+        );
+        assert_eq!(
+            read_error(parse("size 2 1\n\nwall 0 0 1 0\n")),
+            "line 3: unknown line 'wall 0 0 1 0'"
+        );
+        assert_eq!(
+            read_error(parse("size 2 1\ncost 1 0 0\n")),
+            "line 2: invalid cost 0 of cell (1, 0)"
+        );
+        assert_eq!(
+            read_error(parse("size 2 1\nsolution 0 0 2 0\n")),
+            "line 2: cell (2, 0) is out of the maze"
+        );
+    }
+}
@@ -0,0 +1,462 @@
+//! JSON documents for mazes and generation traces.
+//!
+//! Cells are `[x, y]` arrays and passages `[x1, y1, x2, y2]` arrays. A maze:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "width": 2,
+//!   "height": 2,
+//!   "topology": "orthogonal",
+//!   "passages": [[0, 0, 1, 0], [0, 0, 0, 1], [1, 0, 1, 1]],
+//!   "void": [],
+//!   "costs": [[1, 0, 3]],
+//!   "solution": [[0, 0], [1, 0], [1, 1]]
+//! }
+//! ```
+//!
+//! `void` lists the cells out of the maze, `costs` the cells which don't cost
+//! 1 to walk into as `[x, y, cost]`. Both can be left out, like `solution`
+//! and `trace` (every cell the solver walked through).
+//! A generation trace lists the events of a generation in order. The cells
+//! masked out of the grid before it started, if any, are listed in `void`:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "generator": "wilson",
+//!   "seed": 42,
+//!   "width": 2,
+//!   "height": 2,
+//!   "events": [
+//!     { "type": "walk_started", "cell": [1, 1] },
+//!     { "type": "walk_entered", "cell": [1, 0] },
+//!     { "type": "loop_erased", "cells": [[0, 0]] },
+//!     { "type": "passage_carved", "from": [1, 0], "to": [1, 1] },
+//!     { "type": "backtracked", "cell": [1, 0] },
+//!     { "type": "cell_removed", "cell": [0, 1] }
+//!   ]
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::format::WriteOptions;
+use crate::maze_generator::{GenerationEvent, Generator};
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+
+pub const VERSION: u32 = 1;
+pub const TOPOLOGY: &str = "orthogonal";
+
+type CellDocument = [usize; 2];
+
+#[derive(Serialize, Deserialize)]
+struct MazeDocument {
+    version: u32,
+    width: u16,
+    height: u16,
+    topology: String,
+    passages: Vec<[usize; 4]>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    void: Vec<CellDocument>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    costs: Vec<[usize; 3]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    solution: Option<Vec<CellDocument>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    trace: Option<Vec<CellDocument>>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum EventDocument {
+    WalkStarted {
+        cell: CellDocument,
+    },
+    WalkEntered {
+        cell: CellDocument,
+    },
+    LoopErased {
+        cells: Vec<CellDocument>,
+    },
+    PassageCarved {
+        from: CellDocument,
+        to: CellDocument,
+    },
+    Backtracked {
+        cell: CellDocument,
+    },
+    CellRemoved {
+        cell: CellDocument,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct GenerationTraceDocument {
+    version: u32,
+    generator: String,
+    seed: u64,
+    width: u16,
+    height: u16,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    void: Vec<CellDocument>,
+    events: Vec<EventDocument>,
+}
+
+/// A maze read back from JSON, with the paths saved along with it
+pub struct MazeFile {
+    pub grid: Grid,
+    pub solution: Option<Vec<Position>>,
+    pub trace: Option<Vec<Position>>,
+}
+
+/// Every event of a generation, enough to replay it
+pub struct GenerationTrace {
+    pub generator: Generator,
+    pub seed: u64,
+    pub number_of_cells_x: u16,
+    pub number_of_cells_y: u16,
+    /// Cells out of the maze from the start, for masked grids
+    pub void_cells: Vec<Position>,
+    pub events: Vec<GenerationEvent>,
+}
+
+fn cell_document(pos: Position) -> CellDocument {
+    [pos.x, pos.y]
+}
+
+fn cells_document(positions: &[Position]) -> Vec<CellDocument> {
+    positions.iter().copied().map(cell_document).collect()
+}
+
+fn check_version(version: u32) -> Result<(), String> {
+    if version != VERSION {
+        return Err(format!(
+            "unsupported version {}, expected {}",
+            version, VERSION
+        ));
+    }
+    Ok(())
+}
+
+fn read_cell(cell: CellDocument, grid: &Grid) -> Result<Position, String> {
+    let pos = Position {
+        x: cell[0],
+        y: cell[1],
+    };
+    if !grid.contains(pos) {
+        return Err(format!("cell ({}, {}) is out of the maze", pos.x, pos.y));
+    }
+    Ok(pos)
+}
+
+fn read_cells(cells: Vec<CellDocument>, grid: &Grid) -> Result<Vec<Position>, String> {
+    cells
+        .into_iter()
+        .map(|cell| read_cell(cell, grid))
+        .collect()
+}
+
+pub fn write_maze(grid: &Grid, options: &WriteOptions) -> String {
+    let (width, height) = grid.get_number_of_cells_x_y();
+    let mut passages: Vec<[usize; 4]> = Vec::new();
+    let mut costs: Vec<[usize; 3]> = Vec::new();
+    for pos in grid.get_cells_positions() {
+        for neighbor in grid.get_neighbors_of_pos(pos) {
+            if (neighbor.y, neighbor.x) > (pos.y, pos.x) {
+                passages.push([pos.x, pos.y, neighbor.x, neighbor.y]);
+            }
+        }
+        if grid.get_cost(pos) != 1 {
+            costs.push([pos.x, pos.y, grid.get_cost(pos) as usize]);
+        }
+    }
+
+    let document = MazeDocument {
+        version: VERSION,
+        width,
+        height,
+        topology: TOPOLOGY.to_owned(),
+        passages,
+        void: cells_document(&grid.get_void_positions()),
+        costs,
+        solution: options.solution.map(cells_document),
+        trace: options.trace.map(cells_document),
+    };
+    serde_json::to_string(&document).unwrap() + "\n"
+}
+
+pub fn read_maze(text: &str) -> Result<MazeFile, String> {
+    let document: MazeDocument =
+        serde_json::from_str(text).map_err(|e| format!("invalid maze document: {}", e))?;
+    check_version(document.version)?;
+    if document.topology != TOPOLOGY {
+        return Err(format!("unsupported topology '{}'", document.topology));
+    }
+
+    // removed first so that passages to void cells are out of the maze
+    let mut grid = Grid::new(document.width, document.height);
+    for pos in read_cells(document.void, &grid)? {
+        grid.remove_cell(pos);
+    }
+    for [x1, y1, x2, y2] in document.passages {
+        let from = read_cell([x1, y1], &grid)?;
+        let to = read_cell([x2, y2], &grid)?;
+        grid.try_add_neighbor(from, to)?;
+    }
+    for [x, y, cost] in document.costs {
+        let pos = read_cell([x, y], &grid)?;
+        let cost = u32::try_from(cost)
+            .ok()
+            .filter(|cost| *cost > 0)
+            .ok_or_else(|| format!("invalid cost {} of cell ({}, {})", cost, x, y))?;
+        grid.set_cost(pos, cost);
+    }
+
+    let solution = document
+        .solution
+        .map(|cells| read_cells(cells, &grid))
+        .transpose()?;
+    let trace = document
+        .trace
+        .map(|cells| read_cells(cells, &grid))
+        .transpose()?;
+    Ok(MazeFile {
+        grid,
+        solution,
+        trace,
+    })
+}
+
+pub fn write_generation_trace(trace: &GenerationTrace) -> String {
+    let events = trace
+        .events
+        .iter()
+        .map(|event| match event {
+            GenerationEvent::WalkStarted(pos) => EventDocument::WalkStarted {
+                cell: cell_document(*pos),
+            },
+            GenerationEvent::WalkEntered(pos) => EventDocument::WalkEntered {
+                cell: cell_document(*pos),
+            },
+            GenerationEvent::LoopErased(positions) => EventDocument::LoopErased {
+                cells: cells_document(positions),
+            },
+            GenerationEvent::PassageCarved(from, to) => EventDocument::PassageCarved {
+                from: cell_document(*from),
+                to: cell_document(*to),
+            },
+            GenerationEvent::Backtracked(pos) => EventDocument::Backtracked {
+                cell: cell_document(*pos),
+            },
+            GenerationEvent::CellRemoved(pos) => EventDocument::CellRemoved {
+                cell: cell_document(*pos),
+            },
+        })
+        .collect();
+
+    let document = GenerationTraceDocument {
+        version: VERSION,
+        generator: trace.generator.name().to_owned(),
+        seed: trace.seed,
+        width: trace.number_of_cells_x,
+        height: trace.number_of_cells_y,
+        void: cells_document(&trace.void_cells),
+        events,
+    };
+    serde_json::to_string(&document).unwrap() + "\n"
+}
+
+/// Reads a generation trace back, checking that its events can be replayed
+pub fn read_generation_trace(text: &str) -> Result<GenerationTrace, String> {
+    let document: GenerationTraceDocument = serde_json::from_str(text)
+        .map_err(|e| format!("invalid generation trace document: {}", e))?;
+    check_version(document.version)?;
+    let generator = Generator::ALL
+        .into_iter()
+        .find(|generator| generator.name() == document.generator)
+        .ok_or_else(|| format!("unknown generator '{}'", document.generator))?;
+
+    // carving the passages as we go catches the invalid ones
+    let mut grid = Grid::new(document.width, document.height);
+    let void_cells = read_cells(document.void, &grid)?;
+    for pos in &void_cells {
+        grid.remove_cell(*pos);
+    }
+    let mut events: Vec<GenerationEvent> = Vec::new();
+    for event in document.events {
+        events.push(match event {
+            EventDocument::WalkStarted { cell } => {
+                GenerationEvent::WalkStarted(read_cell(cell, &grid)?)
+            }
+            EventDocument::WalkEntered { cell } => {
+                GenerationEvent::WalkEntered(read_cell(cell, &grid)?)
+            }
+            EventDocument::LoopErased { cells } => {
+                GenerationEvent::LoopErased(read_cells(cells, &grid)?)
+            }
+            EventDocument::PassageCarved { from, to } => {
+                let from = read_cell(from, &grid)?;
+                let to = read_cell(to, &grid)?;
+                grid.try_add_neighbor(from, to)?;
+                GenerationEvent::PassageCarved(from, to)
+            }
+            EventDocument::Backtracked { cell } => {
+                GenerationEvent::Backtracked(read_cell(cell, &grid)?)
+            }
+            EventDocument::CellRemoved { cell } => {
+                let pos = read_cell(cell, &grid)?;
+                grid.remove_cell(pos);
+                GenerationEvent::CellRemoved(pos)
+            }
+        });
+    }
+
+    Ok(GenerationTrace {
+        generator,
+        seed: document.seed,
+        number_of_cells_x: document.width,
+        number_of_cells_y: document.height,
+        void_cells,
+        events,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::test_mazes::{assert_same_maze, read_error, sample_maze};
+    use crate::mouse::shortest_path;
+
+    // 2x2 maze with the given passages and extra fields
+    fn maze_document(passages: &str, extra: &str) -> String {
+        format!(
+            r#"{{"version":1,"width":2,"height":2,"topology":"orthogonal","passages":{}{}}}"#,
+            passages, extra
+        )
+    }
+
+    #[test]
+    fn round_trips() {
+        let grid = sample_maze();
+        let positions = grid.get_cells_positions();
+        let solution = shortest_path(positions[0], positions[positions.len() - 1], &grid);
+        let options = WriteOptions {
+            solution: Some(&solution),
+            trace: Some(&solution),
+            ..WriteOptions::default()
+        };
+
+        let maze = read_maze(&write_maze(&grid, &options)).unwrap();
+        assert_same_maze(&grid, &maze.grid);
+        assert_eq!(maze.solution, Some(solution.clone()));
+        assert_eq!(maze.trace, Some(solution));
+    }
+
+    #[test]
+    fn round_trips_a_generation_trace() {
+        let void_cells = sample_maze().get_void_positions();
+        let mut empty_grid = Grid::new(6, 3);
+        for pos in &void_cells {
+            empty_grid.remove_cell(*pos);
+        }
+        let mut steps = Generator::RecursiveBacktracker.steps(empty_grid, 3);
+        let trace = GenerationTrace {
+            generator: Generator::RecursiveBacktracker,
+            seed: 3,
+            number_of_cells_x: 6,
+            number_of_cells_y: 3,
+            void_cells: void_cells.clone(),
+            events: steps.by_ref().collect(),
+        };
+
+        let text = write_generation_trace(&trace);
+        let read = read_generation_trace(&text).unwrap();
+        assert_eq!(read.generator, trace.generator);
+        assert_eq!(read.void_cells, void_cells);
+        assert_eq!(read.events.len(), trace.events.len());
+        assert_eq!(write_generation_trace(&read), text);
+    }
+
+    #[test]
+    fn reads_void_cells() {
+        let text = maze_document("[[0,0,1,0],[1,0,1,1]]", r#","void":[[0,1]]"#);
+        let grid = read_maze(&text).unwrap().grid;
+        assert!(!grid.contains(Position { x: 0, y: 1 }));
+        assert_eq!(grid.get_cells_positions().len(), 3);
+    }
+
+    #[test]
+    fn rejects_a_passage_into_a_void_cell() {
+        let text = maze_document("[[0,0,0,1]]", r#","void":[[0,1]]"#);
+        assert_eq!(
+            read_error(read_maze(&text)),
+            "cell (0, 1) is out of the maze"
+        );
+    }
+
+    #[test]
+    fn rejects_a_cell_out_of_the_grid() {
+        let text = maze_document("[[1,0,2,0]]", "");
+        assert_eq!(
+            read_error(read_maze(&text)),
+            "cell (2, 0) is out of the maze"
+        );
+    }
+
+    #[test]
+    fn rejects_cells_which_are_not_adjacent() {
+        let text = maze_document("[[0,0,1,1]]", "");
+        assert_eq!(
+            read_error(read_maze(&text)),
+            "(0, 0) and (1, 1) are not next to each other"
+        );
+    }
+
+    #[test]
+    fn rejects_a_passage_listed_twice() {
+        let text = maze_document("[[0,0,1,0],[1,0,0,0]]", "");
+        assert_eq!(
+            read_error(read_maze(&text)),
+            "passage between (1, 0) and (0, 0) listed twice"
+        );
+    }
+
+    #[test]
+    fn rejects_a_solution_out_of_the_grid() {
+        let text = maze_document("[]", r#","solution":[[0,0],[0,2]]"#);
+        assert_eq!(
+            read_error(read_maze(&text)),
+            "cell (0, 2) is out of the maze"
+        );
+    }
+
+    #[test]
+    fn rejects_an_invalid_cost() {
+        let text = maze_document("[]", r#","costs":[[0,0,0]]"#);
+        assert_eq!(
+            read_error(read_maze(&text)),
+            "invalid cost 0 of cell (0, 0)"
+        );
+    }
+
+    #[test]
+    fn rejects_another_version_or_topology() {
+        let text = maze_document("[]", "").replace(r#""version":1"#, r#""version":7"#);
+        assert_eq!(
+            read_error(read_maze(&text)),
+            "unsupported version 7, expected 1"
+        );
+        let text = maze_document("[]", "").replace("orthogonal", "hexagonal");
+        assert_eq!(
+            read_error(read_maze(&text)),
+            "unsupported topology 'hexagonal'"
+        );
+    }
+
+    #[test]
+    fn rejects_a_broken_document() {
+        assert!(read_error(read_maze("{")).starts_with("invalid maze document"));
+    }
+}
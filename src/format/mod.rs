@@ -0,0 +1,260 @@
+pub mod ascii;
+pub mod binary;
+pub mod edges;
+pub mod json;
+pub mod raster;
+pub mod recording;
+pub mod svg;
+pub mod terminal;
+
+use std::collections::HashMap;
+
+use crate::constants::render_constants::{
+    ACCENT_RGB, BACKGROUND_RGB, CELL_RGB, COSTLY_RGB, HEATMAP_FAR_RGB, HEATMAP_NEAR_RGB,
+    LEP_PATH_RGB, PATH_RGB, START_RGB, WALL_RATIO,
+};
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+
+pub type Rgb = [u8; 3];
+
+/// Look of the image formats
+#[derive(Debug, Clone, Copy)]
+pub struct ImageStyle {
+    /// Side of a cell, in pixels
+    pub cell_size: u32,
+    /// Part of a cell taken by each of its walls, like the viewer's `WALL_RATIO`
+    pub wall_ratio: f64,
+    pub wall_color: Rgb,
+    pub cell_color: Rgb,
+    pub solution_color: Rgb,
+    pub start_color: Rgb,
+    pub goal_color: Rgb,
+    /// Loops erased from the walks of Wilson's algorithm, in recordings
+    pub erased_loop_color: Rgb,
+    /// Colors of the closest and farthest cells of a heatmap
+    pub heatmap_near_color: Rgb,
+    pub heatmap_far_color: Rgb,
+    /// Tint of the most expensive cells, cheaper ones are closer to `cell_color`
+    pub costly_color: Rgb,
+}
+
+// Takes the void cells read from a file out of the grid, refusing the ones
+// with a passage
+fn remove_void_cells(grid: &mut Grid, void_cells: &[Position]) -> Result<(), String> {
+    for pos in void_cells {
+        if !grid.get_neighbors_of_pos(*pos).is_empty() {
+            return Err(format!("void cell ({}, {}) has a passage", pos.x, pos.y));
+        }
+        grid.remove_cell(*pos);
+    }
+    Ok(())
+}
+
+// Color `ratio` of the way from `from` to `to`
+fn blend(from: Rgb, to: Rgb, ratio: f64) -> Rgb {
+    let channel =
+        |i: usize| (from[i] as f64 + (to[i] as f64 - from[i] as f64) * ratio).round() as u8;
+    [channel(0), channel(1), channel(2)]
+}
+
+impl ImageStyle {
+    /// Black walls on white, for printing
+    pub fn print() -> ImageStyle {
+        Self {
+            cell_size: 20,
+            wall_ratio: WALL_RATIO,
+            wall_color: [0, 0, 0],
+            cell_color: [255, 255, 255],
+            solution_color: PATH_RGB,
+            start_color: START_RGB,
+            goal_color: ACCENT_RGB,
+            erased_loop_color: LEP_PATH_RGB,
+            heatmap_near_color: HEATMAP_NEAR_RGB,
+            heatmap_far_color: HEATMAP_FAR_RGB,
+            costly_color: COSTLY_RGB,
+        }
+    }
+
+    /// Same colors as the viewer
+    pub fn viewer() -> ImageStyle {
+        Self {
+            wall_color: BACKGROUND_RGB,
+            cell_color: CELL_RGB,
+            ..ImageStyle::print()
+        }
+    }
+
+    /// Color of a cell `distance` steps away from the origin of a heatmap,
+    /// whose farthest cell is `farthest` steps away
+    pub fn heatmap_color(&self, distance: usize, farthest: usize) -> Rgb {
+        let ratio = distance as f64 / farthest.max(1) as f64;
+        blend(self.heatmap_near_color, self.heatmap_far_color, ratio)
+    }
+
+    /// Color of a cell costing `cost` to walk into, the most expensive cell
+    /// of the maze costing `max_cost`
+    pub fn cost_color(&self, cost: u32, max_cost: u32) -> Rgb {
+        if max_cost <= 1 {
+            return self.cell_color;
+        }
+        let ratio = cost.saturating_sub(1) as f64 / (max_cost - 1) as f64;
+        blend(self.cell_color, self.costly_color, ratio)
+    }
+
+    /// Thickness of a wall between two cells, in pixels
+    pub fn wall_thickness(&self) -> f64 {
+        2.0 * self.wall_ratio * self.cell_size as f64
+    }
+}
+
+/// Extra content and settings of the formats, each one uses what it supports
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WriteOptions<'a> {
+    pub solution: Option<&'a [Position]>,
+    /// Every cell a solver walked through
+    pub trace: Option<&'a [Position]>,
+    /// ANSI colors in the terminal formats
+    pub colors: bool,
+    /// Seed the maze was generated with, kept by the binary format
+    pub seed: Option<u64>,
+    /// Cells marked as the start and the goal by the image formats
+    pub start: Option<Position>,
+    pub goal: Option<Position>,
+    /// Distance of the cells to some origin, shown as a heatmap by the image formats
+    pub distances: Option<&'a HashMap<Position, usize>>,
+    /// Each image format has its own default style
+    pub style: Option<ImageStyle>,
+}
+
+/// Output formats of the headless CLI
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    Edges,
+    Ascii,
+    Unicode,
+    HalfBlocks,
+    Json,
+    Binary,
+    Svg,
+    Png,
+}
+
+impl Format {
+    pub const ALL: [Format; 8] = [
+        Format::Edges,
+        Format::Ascii,
+        Format::Unicode,
+        Format::HalfBlocks,
+        Format::Json,
+        Format::Binary,
+        Format::Svg,
+        Format::Png,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Format::Edges => "edges",
+            Format::Ascii => "ascii",
+            Format::Unicode => "unicode",
+            Format::HalfBlocks => "blocks",
+            Format::Json => "json",
+            Format::Binary => "binary",
+            Format::Svg => "svg",
+            Format::Png => "png",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Format> {
+        Format::ALL.into_iter().find(|format| format.name() == name)
+    }
+
+    /// Style used by the image formats when none is given
+    pub fn default_style(&self) -> ImageStyle {
+        match self {
+            Format::Png => ImageStyle::viewer(),
+            _ => ImageStyle::print(),
+        }
+    }
+
+    pub fn write(&self, grid: &Grid, options: &WriteOptions) -> Result<Vec<u8>, String> {
+        Ok(match self {
+            Format::Edges => edges::write(grid, options.solution).into_bytes(),
+            Format::Ascii => ascii::write(grid, options.solution).into_bytes(),
+            Format::Unicode => terminal::write_box_drawing(grid, options).into_bytes(),
+            Format::HalfBlocks => terminal::write_half_blocks(grid, options).into_bytes(),
+            Format::Json => json::write_maze(grid, options).into_bytes(),
+            Format::Binary => binary::write(grid, options.seed),
+            Format::Svg => svg::write(grid, options).into_bytes(),
+            Format::Png => raster::draw_maze(grid, options).to_png()?,
+        })
+    }
+}
+
+// Mazes and helpers shared by the tests of the formats
+#[cfg(test)]
+pub(crate) mod test_mazes {
+    use crate::maze_generator::Generator;
+    use crate::model::cell::Position;
+    use crate::model::grid::Grid;
+    use crate::model::mask::Mask;
+
+    /// Maze with void cells and costs, everything a format may have to store
+    pub fn sample_maze() -> Grid {
+        let mask = Mask::from_ascii(
+            "\
+#####.
+######
+.#####
+",
+        )
+        .unwrap();
+        let mut grid = Generator::Wilson.generate(Grid::from_mask(&mask), 7);
+        grid.set_cost(Position { x: 1, y: 1 }, 3);
+        grid.set_cost(Position { x: 4, y: 2 }, 9);
+        grid
+    }
+
+    /// 2x2 maze going around a wall from (0, 0) to (0, 1)
+    pub fn small_maze() -> Grid {
+        let mut grid = Grid::new(2, 2);
+        grid.add_neighbor(Position { x: 0, y: 0 }, Position { x: 1, y: 0 });
+        grid.add_neighbor(Position { x: 1, y: 0 }, Position { x: 1, y: 1 });
+        grid.add_neighbor(Position { x: 1, y: 1 }, Position { x: 0, y: 1 });
+        grid
+    }
+
+    /// Path of `small_maze` from (0, 0) to (0, 1)
+    pub fn small_maze_solution() -> Vec<Position> {
+        vec![
+            Position { x: 0, y: 0 },
+            Position { x: 1, y: 0 },
+            Position { x: 1, y: 1 },
+            Position { x: 0, y: 1 },
+        ]
+    }
+
+    pub fn assert_same_maze(grid: &Grid, other: &Grid) {
+        assert_eq!(
+            grid.get_number_of_cells_x_y(),
+            other.get_number_of_cells_x_y()
+        );
+        assert_eq!(grid.get_cells_positions(), other.get_cells_positions());
+        for pos in grid.get_cells_positions() {
+            let mut neighbors = grid.get_neighbors_of_pos(pos);
+            let mut other_neighbors = other.get_neighbors_of_pos(pos);
+            neighbors.sort_by_key(|pos| (pos.y, pos.x));
+            other_neighbors.sort_by_key(|pos| (pos.y, pos.x));
+            assert_eq!(neighbors, other_neighbors, "neighbors of {:?}", pos);
+            assert_eq!(grid.get_cost(pos), other.get_cost(pos), "cost of {:?}", pos);
+        }
+    }
+
+    /// Error of a reader expected to refuse its input
+    pub fn read_error<T>(result: Result<T, String>) -> String {
+        match result {
+            Ok(_) => panic!("the maze was read"),
+            Err(error) => error,
+        }
+    }
+}
@@ -0,0 +1,368 @@
+//! Software rendering of mazes, drawn like the viewer does but without a
+//! window, and reading of mazes back from images.
+
+use crate::format::{ImageStyle, Rgb, WriteOptions, remove_void_cells};
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+use crate::model::mask::Mask;
+
+/// RGB image, row by row
+#[derive(Clone)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32, color: Rgb) -> Image {
+        Image {
+            width,
+            height,
+            pixels: color.repeat(width as usize * height as usize),
+        }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Rgb {
+        let index = 3 * (y as usize * self.width as usize + x as usize);
+        [
+            self.pixels[index],
+            self.pixels[index + 1],
+            self.pixels[index + 2],
+        ]
+    }
+
+    /// Fills a rectangle, clipped to the image
+    pub fn fill_rectangle(&mut self, x: u32, y: u32, width: u32, height: u32, color: Rgb) {
+        let left = x.min(self.width) as usize;
+        let right = (x + width).min(self.width) as usize;
+        let bottom = (y + height).min(self.height) as usize;
+        for row in y as usize..bottom {
+            let row_start = row * self.width as usize;
+            let pixels = &mut self.pixels[3 * (row_start + left)..3 * (row_start + right)];
+            for pixel in pixels.chunks_exact_mut(3) {
+                pixel.copy_from_slice(&color);
+            }
+        }
+    }
+
+    /// Decodes a PNG of any color type, dropping the transparency
+    pub fn from_png(bytes: &[u8]) -> Result<Image, String> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder
+            .read_info()
+            .map_err(|e| format!("invalid png: {}", e))?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader
+            .next_frame(&mut buffer)
+            .map_err(|e| format!("invalid png: {}", e))?;
+        let data = &buffer[..info.buffer_size()];
+
+        let pixels = match info.color_type {
+            png::ColorType::Rgb => data.to_vec(),
+            png::ColorType::Rgba => data
+                .chunks_exact(4)
+                .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+                .collect(),
+            png::ColorType::Grayscale => data.iter().flat_map(|gray| [*gray; 3]).collect(),
+            png::ColorType::GrayscaleAlpha => data
+                .chunks_exact(2)
+                .flat_map(|pixel| [pixel[0]; 3])
+                .collect(),
+            // expanded to RGB by the transformations
+            png::ColorType::Indexed => unreachable!(),
+        };
+        Ok(Image {
+            width: info.width,
+            height: info.height,
+            pixels,
+        })
+    }
+
+    /// Fails on an empty image, or one too large for the encoder
+    pub fn to_png(&self) -> Result<Vec<u8>, String> {
+        let mut bytes: Vec<u8> = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let error = |e: png::EncodingError| format!("cannot encode PNG: {}", e);
+        let mut writer = encoder.write_header().map_err(error)?;
+        writer.write_image_data(&self.pixels).map_err(error)?;
+        writer.finish().map_err(error)?;
+        Ok(bytes)
+    }
+}
+
+/// Fills a cell the way the viewer's `display_grid` does: the whole cell
+/// minus a margin of `wall_ratio` on each side closed by a wall.
+pub fn draw_cell(image: &mut Image, grid: &Grid, style: &ImageStyle, pos: Position, color: Rgb) {
+    let cell_size = style.cell_size;
+    // walls stay visible in small thumbnails
+    let mut wall_thickness = (style.wall_ratio * cell_size as f64).round() as u32;
+    if style.wall_ratio > 0.0 {
+        wall_thickness = wall_thickness.max(1);
+    }
+    let closed =
+        |other: Option<Position>| !other.is_some_and(|other| grid.are_neighbors(pos, other));
+
+    let mut left = pos.x as u32 * cell_size;
+    let mut top = pos.y as u32 * cell_size;
+    let mut right = left + cell_size;
+    let mut bottom = top + cell_size;
+    if closed(pos.x.checked_sub(1).map(|x| Position { x, y: pos.y })) {
+        left += wall_thickness;
+    }
+    if closed(Some(Position {
+        x: pos.x + 1,
+        y: pos.y,
+    })) {
+        right -= wall_thickness;
+    }
+    if closed(pos.y.checked_sub(1).map(|y| Position { x: pos.x, y })) {
+        top += wall_thickness;
+    }
+    if closed(Some(Position {
+        x: pos.x,
+        y: pos.y + 1,
+    })) {
+        bottom -= wall_thickness;
+    }
+
+    image.fill_rectangle(
+        left,
+        top,
+        right.saturating_sub(left),
+        bottom.saturating_sub(top),
+        color,
+    );
+}
+
+/// Draws the maze with the distance heatmap, the solution and the start and
+/// goal cells on top of it. Without explicit start and goal, the ends of the
+/// solution are marked. Void cells keep the color of the walls and cells
+/// expensive to walk into are tinted.
+pub fn draw_maze(grid: &Grid, options: &WriteOptions) -> Image {
+    let style = options.style.unwrap_or_else(ImageStyle::viewer);
+    let (number_of_cells_x, number_of_cells_y) = grid.get_number_of_cells_x_y();
+    let mut image = Image::new(
+        number_of_cells_x as u32 * style.cell_size,
+        number_of_cells_y as u32 * style.cell_size,
+        style.wall_color,
+    );
+
+    let farthest = options
+        .distances
+        .and_then(|distances| distances.values().max().copied())
+        .unwrap_or(0);
+    let max_cost = grid.get_max_cost();
+    for pos in grid.get_cells_positions() {
+        let color = match options.distances.and_then(|distances| distances.get(&pos)) {
+            Some(distance) => style.heatmap_color(*distance, farthest),
+            None => style.cost_color(grid.get_cost(pos), max_cost),
+        };
+        draw_cell(&mut image, grid, &style, pos, color);
+    }
+
+    let solution = options.solution.unwrap_or(&[]);
+    for pos in solution {
+        draw_cell(&mut image, grid, &style, *pos, style.solution_color);
+    }
+    if let Some(start) = options.start.or(solution.first().copied()) {
+        draw_cell(&mut image, grid, &style, start, style.start_color);
+    }
+    if let Some(goal) = options.goal.or(solution.last().copied()) {
+        draw_cell(&mut image, grid, &style, goal, style.goal_color);
+    }
+    image
+}
+
+// pixels darker than this are walls, the accent and heatmap colors of the
+// viewer are lighter
+const WALL_LUMINANCE: f64 = 80.0;
+
+fn is_wall_pixel(image: &Image, x: u32, y: u32) -> bool {
+    let [r, g, b] = image.pixel(x, y);
+    0.299 * r as f64 + 0.587 * g as f64 + 0.114 * (b as f64) < WALL_LUMINANCE
+}
+
+/// Reads a maze from an image of dark walls on a light background, with the
+/// top left cell in its top left corner. Each wall is looked for in a band
+/// around the border of its two cells, along the middle half of the border
+/// to stay clear of the corners, and is kept if it crosses most of the band.
+/// Cells whose middle is as dark as the walls are void, like the ones
+/// `draw_maze` leaves the color of the walls. The image must be a whole
+/// number of cells, closed by walls along its edges.
+pub fn read_maze(image: &Image, cell_size: u32) -> Result<Grid, String> {
+    if cell_size < 4 {
+        return Err(format!(
+            "cell size {} is too small, at least 4 pixels are needed",
+            cell_size
+        ));
+    }
+    if !image.width.is_multiple_of(cell_size) || !image.height.is_multiple_of(cell_size) {
+        return Err(format!(
+            "{}x{} image is not a whole number of {} pixel cells",
+            image.width, image.height, cell_size
+        ));
+    }
+    let number_of_cells_x = image.width / cell_size;
+    let number_of_cells_y = image.height / cell_size;
+    if number_of_cells_x < 2 || number_of_cells_y < 2 {
+        return Err(format!(
+            "{}x{} image is too small for cells of {} pixels",
+            image.width, image.height, cell_size
+        ));
+    }
+    let number_of_cells_x = u16::try_from(number_of_cells_x)
+        .map_err(|_| format!("too many cells: {}", number_of_cells_x))?;
+    let number_of_cells_y = u16::try_from(number_of_cells_y)
+        .map_err(|_| format!("too many cells: {}", number_of_cells_y))?;
+
+    let band = (cell_size / 8).max(1);
+    // whether the border at `across` is dark somewhere in the band at `along`
+    let crosses_vertical = |along: u32, across: u32| {
+        (across.saturating_sub(band)..(across + band).min(image.width))
+            .any(|x| is_wall_pixel(image, x, along))
+    };
+    let crosses_horizontal = |along: u32, across: u32| {
+        (across.saturating_sub(band)..(across + band).min(image.height))
+            .any(|y| is_wall_pixel(image, along, y))
+    };
+    // `start` is the first pixel of the cells along the border
+    let has_wall = |start: u32, crosses: &dyn Fn(u32) -> bool| {
+        let middle = start + cell_size / 4..start + 3 * cell_size / 4;
+        let crossed = middle.clone().filter(|along| crosses(*along)).count();
+        2 * crossed > middle.len()
+    };
+
+    let is_void = |left: u32, top: u32| {
+        let middle = |start: u32| start + cell_size / 4..start + 3 * cell_size / 4;
+        let pixels = middle(left).len() * middle(top).len();
+        let dark = middle(top)
+            .flat_map(|y| middle(left).map(move |x| (x, y)))
+            .filter(|(x, y)| is_wall_pixel(image, *x, *y))
+            .count();
+        2 * dark > pixels
+    };
+
+    let mut grid = Grid::new(number_of_cells_x, number_of_cells_y);
+    let mut void_cells: Vec<Position> = Vec::new();
+    for y in 0..number_of_cells_y as usize {
+        for x in 0..number_of_cells_x as usize {
+            let pos = Position { x, y };
+            let (left, top) = (x as u32 * cell_size, y as u32 * cell_size);
+            let right_border = |along| crosses_vertical(along, left + cell_size);
+            let bottom_border = |along| crosses_horizontal(along, top + cell_size);
+            if is_void(left, top) {
+                void_cells.push(pos);
+            } else if (x == 0 && !has_wall(top, &|along| crosses_vertical(along, 0)))
+                || (y == 0 && !has_wall(left, &|along| crosses_horizontal(along, 0)))
+                || (x + 1 == number_of_cells_x as usize && !has_wall(top, &right_border))
+                || (y + 1 == number_of_cells_y as usize && !has_wall(left, &bottom_border))
+            {
+                // the grid is shifted, or its cells aren't `cell_size` wide
+                return Err(format!(
+                    "cell ({}, {}) opens outside of the image, is the cell size right?",
+                    x, y
+                ));
+            }
+            if x + 1 < number_of_cells_x as usize && !has_wall(top, &right_border) {
+                grid.add_neighbor(pos, Position { x: x + 1, y });
+            }
+            if y + 1 < number_of_cells_y as usize && !has_wall(left, &bottom_border) {
+                grid.add_neighbor(pos, Position { x, y: y + 1 });
+            }
+        }
+    }
+    remove_void_cells(&mut grid, &void_cells)?;
+    Ok(grid)
+}
+
+/// Reads a mask from an image with one pixel per cell, the dark pixels being
+/// the cells of the maze and the light ones void
+pub fn read_mask(image: &Image) -> Result<Mask, String> {
+    let number_of_cells_x =
+        u16::try_from(image.width).map_err(|_| format!("too many cells: {}", image.width))?;
+    let number_of_cells_y =
+        u16::try_from(image.height).map_err(|_| format!("too many cells: {}", image.height))?;
+    let enabled = (0..image.height)
+        .flat_map(|y| (0..image.width).map(move |x| is_wall_pixel(image, x, y)))
+        .collect();
+    Mask::new(number_of_cells_x, number_of_cells_y, enabled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::test_mazes::{assert_same_maze, read_error, sample_maze};
+
+    // the sample maze as a png, without its costs which images only tint
+    fn sample_image() -> (Grid, Image) {
+        let mut grid = sample_maze();
+        for pos in grid.get_cells_positions() {
+            grid.set_cost(pos, 1);
+        }
+        let png = draw_maze(&grid, &WriteOptions::default()).to_png().unwrap();
+        (grid, Image::from_png(&png).unwrap())
+    }
+
+    #[test]
+    fn round_trips() {
+        let (grid, image) = sample_image();
+        let cell_size = ImageStyle::viewer().cell_size;
+        assert_same_maze(&grid, &read_maze(&image, cell_size).unwrap());
+    }
+
+    #[test]
+    fn rejects_a_wrong_cell_size() {
+        let (_, image) = sample_image();
+        assert_eq!(
+            read_error(read_maze(&image, 3)),
+            "cell size 3 is too small, at least 4 pixels are needed"
+        );
+        // cells of 30 pixels straddle two of the maze's, a void one then
+        // seems to open on the next one
+        assert_eq!(
+            read_error(read_maze(&image, 30)),
+            "void cell (3, 0) has a passage"
+        );
+        assert_eq!(
+            read_error(read_maze(&image, 25)),
+            "120x60 image is not a whole number of 25 pixel cells"
+        );
+    }
+
+    #[test]
+    fn rejects_an_image_not_aligned_to_the_grid() {
+        let (_, image) = sample_image();
+        // the maze moved 5 pixels to the right, cut on the right side
+        let mut shifted = Image::new(image.width, image.height, [255, 255, 255]);
+        for y in 0..image.height {
+            for x in 0..image.width - 5 {
+                let color = image.pixel(x, y);
+                shifted.fill_rectangle(x + 5, y, 1, 1, color);
+            }
+        }
+        assert_eq!(
+            read_error(read_maze(&shifted, 20)),
+            "cell (0, 0) opens outside of the image, is the cell size right?"
+        );
+    }
+
+    #[test]
+    fn reads_a_mask_from_dark_pixels() {
+        let mut image = Image::new(3, 2, [255, 255, 255]);
+        for (x, y) in [(0, 0), (1, 0), (1, 1), (2, 1)] {
+            image.fill_rectangle(x, y, 1, 1, [0, 0, 0]);
+        }
+        let mask = read_mask(&image).unwrap();
+        assert_eq!(mask.get_number_of_cells_x_y(), (3, 2));
+        assert!(mask.is_enabled(Position { x: 1, y: 1 }));
+        assert!(!mask.is_enabled(Position { x: 2, y: 0 }));
+        assert!(!mask.is_enabled(Position { x: 0, y: 1 }));
+
+        image.fill_rectangle(1, 1, 1, 1, [255, 255, 255]);
+        assert!(read_mask(&image).is_err());
+    }
+}
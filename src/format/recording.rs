@@ -0,0 +1,367 @@
+//! Headless recordings of the viewer's generation and solving animations,
+//! as frames to save one by one or to put in an animated GIF.
+
+use std::borrow::Cow;
+
+use crate::format::raster::{Image, draw_cell};
+use crate::format::{ImageStyle, Rgb};
+use crate::maze_generator::{GenerationEvent, GenerationSnapshot};
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+
+/// GIF delays are counted in hundredths of a second, and most viewers slow
+/// down frames shorter than two of them
+pub const MAX_FRAMES_PER_SECOND: u32 = 50;
+pub const DEFAULT_FRAMES_PER_SECOND: u32 = 50;
+// default speeds of the recorded animations, in steps per second
+pub const GENERATION_STEPS_PER_SECOND: f32 = 120.0;
+pub const SOLVING_STEPS_PER_SECOND: f32 = 30.0;
+/// How fast a recording plays
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pace {
+    pub steps_per_second: f32,
+    pub frames_per_second: u32,
+}
+
+// the finished maze stays on screen before the animation loops, in
+// hundredths of a second
+const LAST_FRAME_DELAY: u16 = 200;
+
+// Calls `on_frame` with the number of steps to show in each frame, at the
+// given speed, until all of them are shown
+fn for_each_frame(
+    number_of_steps: usize,
+    pace: Pace,
+    mut on_frame: impl FnMut(usize) -> Result<(), String>,
+) -> Result<(), String> {
+    let steps_per_frame = pace.steps_per_second as f64 / pace.frames_per_second.max(1) as f64;
+    let mut frame = 0;
+    loop {
+        let steps = ((frame as f64 * steps_per_frame) as usize).min(number_of_steps);
+        on_frame(steps)?;
+        if steps == number_of_steps {
+            return Ok(());
+        }
+        frame += 1;
+    }
+}
+
+fn draw_positions(
+    image: &mut Image,
+    grid: &Grid,
+    style: &ImageStyle,
+    positions: &[Position],
+    color: Rgb,
+) {
+    for pos in positions {
+        draw_cell(image, grid, style, *pos, color);
+    }
+}
+
+/// Frames of the generation, drawn like the viewer's: the loop-erased walk
+/// with its start and its head, and the loops as they are erased.
+pub fn record_generation(
+    empty_grid: &Grid,
+    events: &[GenerationEvent],
+    style: &ImageStyle,
+    pace: Pace,
+    mut on_frame: impl FnMut(&Image) -> Result<(), String>,
+) -> Result<(), String> {
+    let (number_of_cells_x, number_of_cells_y) = empty_grid.get_number_of_cells_x_y();
+    let mut snapshot = GenerationSnapshot::new(empty_grid);
+    let mut applied = 0;
+
+    for_each_frame(events.len(), pace, |steps| {
+        for event in &events[applied..steps] {
+            snapshot.apply(event);
+        }
+        applied = steps;
+
+        let grid = &snapshot.grid;
+        let mut image = Image::new(
+            number_of_cells_x as u32 * style.cell_size,
+            number_of_cells_y as u32 * style.cell_size,
+            style.wall_color,
+        );
+        draw_positions(
+            &mut image,
+            grid,
+            style,
+            &grid.get_cells_positions(),
+            style.cell_color,
+        );
+        let walk = &snapshot.loop_erased_walk;
+        draw_positions(&mut image, grid, style, walk, style.solution_color);
+        if let Some(last) = walk.last() {
+            draw_cell(&mut image, grid, style, *last, style.goal_color);
+        }
+        if let Some(start) = walk.first() {
+            draw_cell(&mut image, grid, style, *start, style.start_color);
+        }
+        draw_positions(
+            &mut image,
+            grid,
+            style,
+            &snapshot.erased_loop,
+            style.erased_loop_color,
+        );
+        on_frame(&image)
+    })
+}
+
+/// Frames of a solver walking through the maze from `start_pos` to `end_pos`
+pub fn record_solving(
+    grid: &Grid,
+    trace: &[Position],
+    start_pos: Position,
+    end_pos: Position,
+    style: &ImageStyle,
+    pace: Pace,
+    mut on_frame: impl FnMut(&Image) -> Result<(), String>,
+) -> Result<(), String> {
+    let (number_of_cells_x, number_of_cells_y) = grid.get_number_of_cells_x_y();
+    let mut maze = Image::new(
+        number_of_cells_x as u32 * style.cell_size,
+        number_of_cells_y as u32 * style.cell_size,
+        style.wall_color,
+    );
+    let max_cost = grid.get_max_cost();
+    for pos in grid.get_cells_positions() {
+        let color = style.cost_color(grid.get_cost(pos), max_cost);
+        draw_cell(&mut maze, grid, style, pos, color);
+    }
+    draw_cell(&mut maze, grid, style, start_pos, style.start_color);
+    draw_cell(&mut maze, grid, style, end_pos, style.goal_color);
+
+    // the walk so far is drawn once, only the head moves
+    let mut drawn = 0;
+    for_each_frame(trace.len(), pace, |steps| {
+        draw_positions(
+            &mut maze,
+            grid,
+            style,
+            &trace[drawn..steps],
+            style.solution_color,
+        );
+        drawn = steps;
+
+        let mut image = maze.clone();
+        if steps > 0 {
+            draw_cell(&mut image, grid, style, trace[steps - 1], style.goal_color);
+        }
+        on_frame(&image)
+    })
+}
+
+// Smallest rectangle holding every pixel that differs, as (left, top, right, bottom)
+fn changed_rectangle(previous: &Image, image: &Image) -> Option<(u32, u32, u32, u32)> {
+    let row_size = 3 * image.width as usize;
+    let mut rectangle: Option<(u32, u32, u32, u32)> = None;
+    for y in 0..image.height {
+        let row = y as usize * row_size..(y as usize + 1) * row_size;
+        if previous.pixels[row.clone()] == image.pixels[row] {
+            continue;
+        }
+        for x in 0..image.width {
+            if previous.pixel(x, y) == image.pixel(x, y) {
+                continue;
+            }
+            rectangle = Some(match rectangle {
+                None => (x, y, x + 1, y + 1),
+                Some((left, top, right, bottom)) => {
+                    (left.min(x), top.min(y), right.max(x + 1), bottom.max(y + 1))
+                }
+            });
+        }
+    }
+    rectangle
+}
+
+/// Encodes frames of the same size into a looping animated GIF. Only the part
+/// of each frame that changed is stored, and identical frames are merged.
+pub struct GifRecorder {
+    encoder: gif::Encoder<Vec<u8>>,
+    // time between two frames, in hundredths of a second
+    delay: u16,
+    previous: Option<Image>,
+    pending: Option<gif::Frame<'static>>,
+}
+
+impl GifRecorder {
+    /// Recorder of frames shown `frames_per_second` times a second, up to
+    /// `MAX_FRAMES_PER_SECOND`
+    pub fn new(width: u32, height: u32, frames_per_second: u32) -> Result<GifRecorder, String> {
+        if !(1..=MAX_FRAMES_PER_SECOND).contains(&frames_per_second) {
+            return Err(format!(
+                "a GIF cannot show {} frames per second, expected 1 to {}",
+                frames_per_second, MAX_FRAMES_PER_SECOND
+            ));
+        }
+        let too_large = || format!("{}x{} frames are too large for a GIF", width, height);
+        let width = u16::try_from(width).map_err(|_| too_large())?;
+        let height = u16::try_from(height).map_err(|_| too_large())?;
+        let mut encoder = gif::Encoder::new(Vec::new(), width, height, &[])
+            .map_err(|e| format!("cannot encode GIF: {}", e))?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(|e| format!("cannot encode GIF: {}", e))?;
+        Ok(GifRecorder {
+            encoder,
+            delay: (100 / frames_per_second) as u16,
+            previous: None,
+            pending: None,
+        })
+    }
+
+    pub fn add_frame(&mut self, image: &Image) -> Result<(), String> {
+        let rectangle = match &self.previous {
+            None => Some((0, 0, image.width, image.height)),
+            Some(previous) => changed_rectangle(previous, image),
+        };
+        match rectangle {
+            None => {
+                if let Some(pending) = &mut self.pending {
+                    pending.delay = pending.delay.saturating_add(self.delay);
+                }
+            }
+            Some(rectangle) => {
+                self.flush()?;
+                self.pending = Some(frame(image, rectangle, self.delay));
+            }
+        }
+        self.previous = Some(image.clone());
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), String> {
+        if let Some(frame) = self.pending.take() {
+            self.encoder
+                .write_frame(&frame)
+                .map_err(|e| format!("cannot encode GIF: {}", e))?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> Result<Vec<u8>, String> {
+        if let Some(pending) = &mut self.pending {
+            pending.delay = pending.delay.saturating_add(LAST_FRAME_DELAY);
+        }
+        self.flush()?;
+        self.encoder
+            .into_inner()
+            .map_err(|e| format!("cannot encode GIF: {}", e))
+    }
+}
+
+// GIF frame of a part of the image, with its own palette of the colors used
+fn frame(
+    image: &Image,
+    (left, top, right, bottom): (u32, u32, u32, u32),
+    delay: u16,
+) -> gif::Frame<'static> {
+    let (width, height) = ((right - left) as u16, (bottom - top) as u16);
+    let mut pixels: Vec<u8> = Vec::with_capacity(3 * width as usize * height as usize);
+    for y in top..bottom {
+        for x in left..right {
+            pixels.extend_from_slice(&image.pixel(x, y));
+        }
+    }
+
+    let mut palette: Vec<Rgb> = Vec::new();
+    let mut indices: Vec<u8> = Vec::with_capacity(width as usize * height as usize);
+    for pixel in pixels.chunks_exact(3) {
+        let color = [pixel[0], pixel[1], pixel[2]];
+        let index = match palette.iter().position(|known| *known == color) {
+            Some(index) => index,
+            None => {
+                palette.push(color);
+                palette.len() - 1
+            }
+        };
+        if index > u8::MAX as usize {
+            // too many colors for a palette, let the encoder pick them
+            let mut frame = gif::Frame::from_rgb(width, height, &pixels);
+            frame.left = left as u16;
+            frame.top = top as u16;
+            frame.delay = delay;
+            return frame;
+        }
+        indices.push(index as u8);
+    }
+
+    gif::Frame {
+        left: left as u16,
+        top: top as u16,
+        width,
+        height,
+        delay,
+        dispose: gif::DisposalMethod::Keep,
+        palette: Some(palette.concat()),
+        buffer: Cow::Owned(indices),
+        ..gif::Frame::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::test_mazes::{small_maze, small_maze_solution};
+
+    // two steps in each frame
+    const PACE: Pace = Pace {
+        steps_per_second: 10.0,
+        frames_per_second: 5,
+    };
+
+    #[test]
+    fn records_a_frame_every_few_generation_steps() {
+        let solution = small_maze_solution();
+        let events: Vec<GenerationEvent> = solution
+            .windows(2)
+            .map(|step| GenerationEvent::PassageCarved(step[0], step[1]))
+            .collect();
+        let style = ImageStyle::viewer();
+        let mut frames: Vec<Image> = Vec::new();
+        record_generation(&Grid::new(2, 2), &events, &style, PACE, |image| {
+            frames.push(image.clone());
+            Ok(())
+        })
+        .unwrap();
+
+        // none, two then all three passages
+        assert_eq!(frames.len(), 3);
+        assert!(
+            frames
+                .iter()
+                .all(|frame| frame.width == 2 * style.cell_size)
+        );
+        assert_ne!(frames[0].pixels, frames[2].pixels);
+    }
+
+    #[test]
+    fn records_a_frame_every_few_solver_steps() {
+        let solution = small_maze_solution();
+        let mut number_of_frames = 0;
+        record_solving(
+            &small_maze(),
+            &solution,
+            solution[0],
+            solution[3],
+            &ImageStyle::viewer(),
+            PACE,
+            |_| {
+                number_of_frames += 1;
+                Ok(())
+            },
+        )
+        .unwrap();
+        // zero, two then four steps
+        assert_eq!(number_of_frames, 3);
+    }
+
+    #[test]
+    fn refuses_a_frame_rate_too_high_for_a_gif() {
+        assert!(GifRecorder::new(10, 10, MAX_FRAMES_PER_SECOND).is_ok());
+        assert!(GifRecorder::new(10, 10, MAX_FRAMES_PER_SECOND + 1).is_err());
+    }
+}
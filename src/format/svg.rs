@@ -0,0 +1,181 @@
+use crate::format::{ImageStyle, Rgb, WriteOptions};
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+
+fn color(rgb: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+}
+
+// shortest decimal writing, svg files get big with a lot of walls
+fn number(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_owned()
+}
+
+// Runs of consecutive walls on a line, as (first, last + 1) indices
+fn runs(has_wall: impl Fn(usize) -> bool, length: usize) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for i in 0..length {
+        if !has_wall(i) {
+            continue;
+        }
+        match runs.last_mut() {
+            Some(run) if run.1 == i => run.1 = i + 1,
+            _ => runs.push((i, i + 1)),
+        }
+    }
+    runs
+}
+
+/// Vector image of the maze. Walls are line segments, merged when they are
+/// aligned, the solution is a polyline through the centers of its cells, the
+/// start and goal are round markers, distances or costs fill the cells.
+/// Without explicit start and goal, the ends of the solution are marked. Void
+/// cells are left blank, without walls between them.
+pub fn write(grid: &Grid, options: &WriteOptions) -> String {
+    let style = options.style.unwrap_or_else(ImageStyle::print);
+    let (number_of_cells_x, number_of_cells_y) = grid.get_number_of_cells_x_y();
+    let (width, height) = (number_of_cells_x as usize, number_of_cells_y as usize);
+    let cell_size = style.cell_size as f64;
+    let wall_thickness = style.wall_thickness();
+    // the outer walls are centered on the border of the maze
+    let margin = wall_thickness / 2.0;
+    let coord = |i: usize| number(margin + i as f64 * cell_size);
+    let center = |i: usize| number(margin + (i as f64 + 0.5) * cell_size);
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+        w = number(2.0 * margin + width as f64 * cell_size),
+        h = number(2.0 * margin + height as f64 * cell_size),
+    );
+    svg += &format!(
+        "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n",
+        color(style.cell_color)
+    );
+
+    let farthest = options
+        .distances
+        .and_then(|distances| distances.values().max().copied())
+        .unwrap_or(0);
+    let max_cost = grid.get_max_cost();
+    for pos in grid.get_cells_positions() {
+        let fill = match options.distances.and_then(|distances| distances.get(&pos)) {
+            Some(distance) => style.heatmap_color(*distance, farthest),
+            None if grid.get_cost(pos) != 1 => style.cost_color(grid.get_cost(pos), max_cost),
+            None => continue,
+        };
+        svg += &format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+            coord(pos.x),
+            coord(pos.y),
+            number(cell_size),
+            number(cell_size),
+            color(fill)
+        );
+    }
+
+    svg += &format!(
+        "<g stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"square\">\n",
+        color(style.wall_color),
+        number(wall_thickness)
+    );
+    for y in 0..=height {
+        let has_wall = |x: usize| match y.checked_sub(1) {
+            Some(top) => grid.has_wall_between(Position { x, y: top }, Position { x, y }),
+            None => grid.contains(Position { x, y }),
+        };
+        for (start, end) in runs(has_wall, width) {
+            svg += &format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n",
+                coord(start),
+                coord(y),
+                coord(end),
+                coord(y)
+            );
+        }
+    }
+    for x in 0..=width {
+        let has_wall = |y: usize| match x.checked_sub(1) {
+            Some(left) => grid.has_wall_between(Position { x: left, y }, Position { x, y }),
+            None => grid.contains(Position { x, y }),
+        };
+        for (start, end) in runs(has_wall, height) {
+            svg += &format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n",
+                coord(x),
+                coord(start),
+                coord(x),
+                coord(end)
+            );
+        }
+    }
+    svg += "</g>\n";
+
+    let solution = options.solution.unwrap_or(&[]);
+    if !solution.is_empty() {
+        let points: Vec<String> = solution
+            .iter()
+            .map(|pos| format!("{},{}", center(pos.x), center(pos.y)))
+            .collect();
+        svg += &format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>\n",
+            points.join(" "),
+            color(style.solution_color),
+            number(cell_size / 4.0)
+        );
+    }
+
+    let markers = [
+        (
+            options.start.or(solution.first().copied()),
+            style.start_color,
+        ),
+        (options.goal.or(solution.last().copied()), style.goal_color),
+    ];
+    for (pos, marker_color) in markers {
+        if let Some(pos) = pos {
+            svg += &format!(
+                "<circle cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>\n",
+                center(pos.x),
+                center(pos.y),
+                number(cell_size / 3.0),
+                color(marker_color)
+            );
+        }
+    }
+
+    svg += "</svg>\n";
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::test_mazes::{small_maze, small_maze_solution};
+
+    #[test]
+    fn draws_the_maze_and_its_solution() {
+        let solution = small_maze_solution();
+        let options = WriteOptions {
+            solution: Some(&solution),
+            ..WriteOptions::default()
+        };
+        assert_eq!(
+            write(&small_maze(), &options),
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="42" height="42" viewBox="0 0 42 42">
+<rect width="100%" height="100%" fill="#ffffff"/>
+<g stroke="#000000" stroke-width="2" stroke-linecap="square">
+<line x1="1" y1="1" x2="41" y2="1"/>
+<line x1="1" y1="21" x2="21" y2="21"/>
+<line x1="1" y1="41" x2="41" y2="41"/>
+<line x1="1" y1="1" x2="1" y2="41"/>
+<line x1="41" y1="1" x2="41" y2="41"/>
+</g>
+<polyline points="11,11 31,11 31,31 11,31" fill="none" stroke="#00e430" stroke-width="5" stroke-linecap="round" stroke-linejoin="round"/>
+<circle cx="11" cy="11" r="6.67" fill="#fdf900"/>
+<circle cx="11" cy="31" r="6.67" fill="#e62937"/>
+</svg>
+"##
+        );
+    }
+}
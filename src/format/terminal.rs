@@ -0,0 +1,321 @@
+use crate::format::WriteOptions;
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+
+// ANSI color numbers, matching the viewer: the solver's walk is green like
+// PATH_COLOR and the solution red like ACCENT_COLOR
+const WALL_ANSI_COLOR: u8 = 7;
+const TRACE_ANSI_COLOR: u8 = 2;
+const SOLUTION_ANSI_COLOR: u8 = 1;
+
+const RESET: &str = "\x1b[0m";
+
+fn is_step(path: Option<&[Position]>, pos1: Position, pos2: Position) -> bool {
+    path.unwrap_or(&[])
+        .windows(2)
+        .any(|step| (step[0] == pos1 && step[1] == pos2) || (step[0] == pos2 && step[1] == pos1))
+}
+
+fn contains(path: Option<&[Position]>, pos: Position) -> bool {
+    path.unwrap_or(&[]).contains(&pos)
+}
+
+// Overlay color of a cell, or of the passage between two cells
+fn overlay_color(options: &WriteOptions, pos1: Position, pos2: Option<Position>) -> Option<u8> {
+    let on = |path: Option<&[Position]>| match pos2 {
+        Some(pos2) => is_step(path, pos1, pos2),
+        None => contains(path, pos1),
+    };
+    if on(options.solution) {
+        Some(SOLUTION_ANSI_COLOR)
+    } else if on(options.trace) {
+        Some(TRACE_ANSI_COLOR)
+    } else {
+        None
+    }
+}
+
+// Walls on the left and top sides of (x, y), which may be past the right or
+// bottom border. The border only has walls next to cells of the maze.
+fn has_left_wall(grid: &Grid, x: usize, y: usize) -> bool {
+    match x.checked_sub(1) {
+        Some(left) => grid.has_wall_between(Position { x: left, y }, Position { x, y }),
+        None => grid.contains(Position { x, y }),
+    }
+}
+
+fn has_top_wall(grid: &Grid, x: usize, y: usize) -> bool {
+    match y.checked_sub(1) {
+        Some(top) => grid.has_wall_between(Position { x, y: top }, Position { x, y }),
+        None => grid.contains(Position { x, y }),
+    }
+}
+
+// Box-drawing character joining the wall segments around a corner
+fn junction(up: bool, down: bool, left: bool, right: bool) -> char {
+    match (up, down, left, right) {
+        (false, false, false, false) => ' ',
+        (true, false, false, false) => '╵',
+        (false, true, false, false) => '╷',
+        (false, false, true, false) => '╴',
+        (false, false, false, true) => '╶',
+        (true, true, false, false) => '│',
+        (false, false, true, true) => '─',
+        (false, true, false, true) => '┌',
+        (false, true, true, false) => '┐',
+        (true, false, false, true) => '└',
+        (true, false, true, false) => '┘',
+        (true, true, false, true) => '├',
+        (true, true, true, false) => '┤',
+        (false, true, true, true) => '┬',
+        (true, false, true, true) => '┴',
+        (true, true, true, true) => '┼',
+    }
+}
+
+fn paint(text: &str, color: Option<u8>, options: &WriteOptions) -> String {
+    match color {
+        Some(color) if options.colors => format!("\x1b[3{}m{}{}", color, text, RESET),
+        _ => text.to_owned(),
+    }
+}
+
+/// Draws the maze with box-drawing characters, each cell being two characters
+/// wide. The solution is drawn with `█` and the solver's walk with `░`, in
+/// color if asked to.
+pub fn write_box_drawing(grid: &Grid, options: &WriteOptions) -> String {
+    let (number_of_cells_x, number_of_cells_y) = grid.get_number_of_cells_x_y();
+    let (width, height) = (number_of_cells_x as usize, number_of_cells_y as usize);
+    // walls around the corner at the top left of cell (x, y), cells may be out of the grid
+    let vertical = |x: usize, y: usize| y < height && has_left_wall(grid, x, y);
+    let horizontal = |x: usize, y: usize| x < width && has_top_wall(grid, x, y);
+
+    let mut text = String::new();
+    for y in 0..=height {
+        for x in 0..=width {
+            let up = y > 0 && vertical(x, y - 1);
+            let left = x > 0 && horizontal(x - 1, y);
+            text.push(junction(up, vertical(x, y), left, horizontal(x, y)));
+            if x < width && horizontal(x, y) {
+                text += "──";
+            } else if x < width && y > 0 && y < height {
+                let top_pos = Position { x, y: y - 1 };
+                let color = overlay_color(options, top_pos, Some(Position { x, y }));
+                text += &fill(color, 2, options);
+            } else if x < width {
+                text += "  ";
+            }
+        }
+        text += "\n";
+        if y == height {
+            break;
+        }
+
+        for x in 0..=width {
+            if vertical(x, y) {
+                text.push('│');
+            } else if x > 0 && x < width {
+                let left_pos = Position { x: x - 1, y };
+                let color = overlay_color(options, left_pos, Some(Position { x, y }));
+                text += &fill(color, 1, options);
+            } else {
+                text.push(' ');
+            }
+            if x < width {
+                let color = overlay_color(options, Position { x, y }, None);
+                text += &fill(color, 2, options);
+            }
+        }
+        text += "\n";
+    }
+    text
+}
+
+fn fill(color: Option<u8>, width: usize, options: &WriteOptions) -> String {
+    let c = match color {
+        Some(SOLUTION_ANSI_COLOR) => "█",
+        Some(_) => "░",
+        None => " ",
+    };
+    paint(&c.repeat(width), color, options)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Block {
+    Empty,
+    Wall,
+    Colored(u8),
+}
+
+fn block_color(block: Block) -> Option<u8> {
+    match block {
+        Block::Empty => None,
+        Block::Wall => Some(WALL_ANSI_COLOR),
+        Block::Colored(color) => Some(color),
+    }
+}
+
+/// Compact rendering where every cell and wall is a single square block, two
+/// rows of blocks being packed in each line with half-block characters.
+/// Overlays are only drawn with colors.
+pub fn write_half_blocks(grid: &Grid, options: &WriteOptions) -> String {
+    let (number_of_cells_x, number_of_cells_y) = grid.get_number_of_cells_x_y();
+    let (width, height) = (number_of_cells_x as usize, number_of_cells_y as usize);
+
+    // cells are at odd coordinates, walls and corners at even ones
+    let block = |column: usize, row: usize| -> Block {
+        if row > 2 * height {
+            return Block::Empty;
+        }
+        let (x, y) = (column / 2, row / 2);
+        let colored = |color: Option<u8>| match color {
+            Some(color) if options.colors => Block::Colored(color),
+            _ => Block::Empty,
+        };
+        match (column % 2, row % 2) {
+            (1, 1) => colored(overlay_color(options, Position { x, y }, None)),
+            (0, 1) if has_left_wall(grid, x, y) => Block::Wall,
+            (0, 1) if x == 0 || x == width => Block::Empty,
+            (0, 1) => colored(overlay_color(
+                options,
+                Position { x: x - 1, y },
+                Some(Position { x, y }),
+            )),
+            (1, 0) if has_top_wall(grid, x, y) => Block::Wall,
+            (1, 0) if y == 0 || y == height => Block::Empty,
+            (1, 0) => colored(overlay_color(
+                options,
+                Position { x, y: y - 1 },
+                Some(Position { x, y }),
+            )),
+            // corners are drawn when a wall reaches them
+            _ if (y > 0 && has_left_wall(grid, x, y - 1))
+                || (y < height && has_left_wall(grid, x, y))
+                || (x > 0 && has_top_wall(grid, x - 1, y))
+                || (x < width && has_top_wall(grid, x, y)) =>
+            {
+                Block::Wall
+            }
+            _ => Block::Empty,
+        }
+    };
+
+    let mut text = String::new();
+    for line in 0..height + 1 {
+        for column in 0..2 * width + 1 {
+            let (top, bottom) = (block(column, 2 * line), block(column, 2 * line + 1));
+            text += &half_blocks(top, bottom, options);
+        }
+        text += "\n";
+    }
+    text
+}
+
+fn half_blocks(top: Block, bottom: Block, options: &WriteOptions) -> String {
+    if !options.colors {
+        let c = match (top != Block::Empty, bottom != Block::Empty) {
+            (true, true) => "█",
+            (true, false) => "▀",
+            (false, true) => "▄",
+            (false, false) => " ",
+        };
+        return c.to_owned();
+    }
+
+    match (block_color(top), block_color(bottom)) {
+        (None, None) => " ".to_owned(),
+        (Some(top), None) => format!("\x1b[3{}m▀{}", top, RESET),
+        (None, Some(bottom)) => format!("\x1b[3{}m▄{}", bottom, RESET),
+        (Some(top), Some(bottom)) => format!("\x1b[3{};4{}m▀{}", top, bottom, RESET),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::test_mazes::{small_maze, small_maze_solution};
+
+    #[test]
+    fn draws_box_drawing_walls() {
+        let text = write_box_drawing(&small_maze(), &WriteOptions::default());
+        assert_eq!(
+            text,
+            "\
+┌─────┐
+│     │
+├──╴  │
+│     │
+└─────┘
+"
+        );
+    }
+
+    #[test]
+    fn draws_the_solution_with_boxes() {
+        let solution = small_maze_solution();
+        let options = WriteOptions {
+            solution: Some(&solution),
+            ..WriteOptions::default()
+        };
+        assert_eq!(
+            write_box_drawing(&small_maze(), &options),
+            "\
+┌─────┐
+│█████│
+├──╴██│
+│█████│
+└─────┘
+"
+        );
+    }
+
+    #[test]
+    fn draws_half_blocks() {
+        let text = write_half_blocks(&small_maze(), &WriteOptions::default());
+        assert_eq!(
+            text,
+            "\
+█▀▀▀█
+█▀▀ █
+▀▀▀▀▀
+"
+        );
+    }
+
+    #[test]
+    fn colors_the_solution_in_half_blocks() {
+        let solution = small_maze_solution();
+        let options = WriteOptions {
+            solution: Some(&solution),
+            colors: true,
+            ..WriteOptions::default()
+        };
+        // white walls over and under the red solution
+        let wall = "\x1b[37;47m▀\x1b[0m";
+        let wall_over_path = "\x1b[37;41m▀\x1b[0m";
+        let path = "\x1b[31;41m▀\x1b[0m";
+        let bottom_wall = "\x1b[37m▀\x1b[0m";
+        let expected = [
+            [
+                wall,
+                wall_over_path,
+                wall_over_path,
+                wall_over_path,
+                wall,
+                "\n",
+            ],
+            [wall, wall_over_path, wall_over_path, path, wall, "\n"],
+            [
+                bottom_wall,
+                bottom_wall,
+                bottom_wall,
+                bottom_wall,
+                bottom_wall,
+                "\n",
+            ],
+        ]
+        .concat()
+        .concat();
+        assert_eq!(write_half_blocks(&small_maze(), &options), expected);
+    }
+}
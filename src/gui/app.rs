@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+
+use macroquad::prelude::*;
+
+use crate::gui::constants::{
+    ACCENT_COLOR, CELL_COLOR, GENERATION_STEPS_PER_SECOND, PATH_COLOR, PATH_STEPS_PER_SECOND,
+};
+use crate::gui::menu::{Menu, Settings, Shape};
+use crate::gui::play::Play;
+use crate::gui::playback::Playback;
+use crate::gui::render::{
+    display_costs, display_generation_step, display_grid, display_heatmap,
+    window_coord_to_game_coord,
+};
+use explorer::maze_generator::braid::braid;
+use explorer::maze_generator::sparsify::sparsify;
+use explorer::maze_generator::terrain::scatter_terrain;
+use explorer::maze_generator::{GenerationEvent, GenerationSnapshot, RoomOptions};
+use explorer::model::cell::Position;
+use explorer::model::grid::Grid;
+use explorer::model::mask::Mask;
+use explorer::mouse::{distances_from, shortest_path};
+
+enum Screen {
+    Menu,
+    // the snapshot after the given number of events, moved forward with the playback
+    Generation(Playback, GenerationSnapshot, usize),
+    Solving(Playback),
+    FreeView,
+    // distances to the origin cell
+    Heatmap(Position, HashMap<Position, usize>),
+    Play(Play),
+}
+
+// The finished maze with its start, goal and costly cells
+fn display_maze(grid: &Grid, start_pos: Position, end_pos: Position) {
+    clear_background(BLACK);
+    display_grid(grid.get_cells_positions(), grid, CELL_COLOR, false);
+    display_costs(grid);
+    display_grid(vec![start_pos], grid, YELLOW, false);
+    display_grid(vec![end_pos], grid, ACCENT_COLOR, false);
+}
+
+/// The whole viewer: a settings menu and the different views of the maze
+/// built from them.
+///
+/// Outside the menu:
+/// - M: back to the menu
+/// - R: regenerate with a new seed
+/// - G / S / F: generation animation, solving animation, free view
+/// - H: distances from the start as a heatmap, click a cell to measure from it
+/// - P: play mode, where only M and R are available as the other keys move the player
+pub struct App {
+    menu: Menu,
+    screen: Screen,
+
+    empty_grid: Grid,
+    grid: Grid,
+    events: Vec<GenerationEvent>,
+    start_pos: Position,
+    end_pos: Position,
+    mouse_path: Vec<Position>,
+    shortest_path: Vec<Position>,
+}
+
+impl App {
+    pub fn new(settings: Settings) -> App {
+        let mut app = Self {
+            menu: Menu::new(settings),
+            screen: Screen::Menu,
+            empty_grid: Grid::new(0, 0),
+            grid: Grid::new(0, 0),
+            events: Vec::new(),
+            start_pos: Position { x: 0, y: 0 },
+            end_pos: Position { x: 0, y: 0 },
+            mouse_path: Vec::new(),
+            shortest_path: Vec::new(),
+        };
+        app.build();
+        app
+    }
+
+    // Generates and solves the maze described by the menu settings
+    fn build(&mut self) {
+        let settings = self.menu.settings().clone();
+        let (number_of_cells_x, number_of_cells_y) =
+            (settings.number_of_cells_x, settings.number_of_cells_y);
+        self.empty_grid = match settings.shape {
+            Shape::Rectangle => Grid::new(number_of_cells_x, number_of_cells_y),
+            // too small grids don't have room for a heart
+            Shape::Heart => Mask::heart(number_of_cells_x, number_of_cells_y)
+                .map(|mask| Grid::from_mask(&mask))
+                .unwrap_or_else(|_| Grid::new(number_of_cells_x, number_of_cells_y)),
+        };
+
+        let mut steps = if settings.rooms > 0 {
+            let rooms = RoomOptions {
+                min_count: settings.rooms,
+                max_count: settings.rooms,
+                ..RoomOptions::default()
+            };
+            settings
+                .generator
+                .steps_with_rooms(self.empty_grid.clone(), &rooms, settings.seed)
+        } else {
+            settings
+                .generator
+                .steps(self.empty_grid.clone(), settings.seed)
+        };
+        self.events = steps.by_ref().collect();
+        self.grid = steps.into_grid();
+        let removed = sparsify(&mut self.grid, settings.sparsify_passes);
+        self.events
+            .extend(removed.into_iter().map(GenerationEvent::CellRemoved));
+        let passages = braid(&mut self.grid, settings.braid, settings.seed);
+        self.events.extend(
+            passages
+                .into_iter()
+                .map(|(pos1, pos2)| GenerationEvent::PassageCarved(pos1, pos2)),
+        );
+        scatter_terrain(&mut self.grid, settings.max_cost, settings.seed);
+
+        (self.start_pos, self.end_pos) = settings.endpoints.place(&self.grid);
+        self.mouse_path = settings
+            .solver
+            .solve(self.start_pos, self.end_pos, &self.grid);
+        self.shortest_path = shortest_path(self.start_pos, self.end_pos, &self.grid);
+    }
+
+    fn generation_playback(&self) -> Screen {
+        Screen::Generation(
+            Playback::new(self.events.len(), GENERATION_STEPS_PER_SECOND),
+            GenerationSnapshot::new(&self.empty_grid),
+            0,
+        )
+    }
+
+    fn solving_playback(&self) -> Screen {
+        Screen::Solving(Playback::new(self.mouse_path.len(), PATH_STEPS_PER_SECOND))
+    }
+
+    fn heatmap(&self, origin: Position) -> Screen {
+        Screen::Heatmap(origin, distances_from(origin, &self.grid))
+    }
+
+    fn handle_screen_keys(&mut self) {
+        if is_key_pressed(KeyCode::M) {
+            self.screen = Screen::Menu;
+        } else if is_key_pressed(KeyCode::R) {
+            self.menu.settings_mut().seed = ::rand::random();
+            self.build();
+            self.screen = self.generation_playback();
+        } else if matches!(self.screen, Screen::Play(_)) {
+            // the other letters are used to move around
+        } else if is_key_pressed(KeyCode::P) {
+            let fog_of_war = self.menu.settings().fog_of_war;
+            self.screen = Screen::Play(Play::new(
+                self.start_pos,
+                self.end_pos,
+                fog_of_war,
+                &self.grid,
+            ));
+        } else if is_key_pressed(KeyCode::G) {
+            self.screen = self.generation_playback();
+        } else if is_key_pressed(KeyCode::S) {
+            self.screen = self.solving_playback();
+        } else if is_key_pressed(KeyCode::F) {
+            self.screen = Screen::FreeView;
+        } else if is_key_pressed(KeyCode::H) {
+            self.screen = self.heatmap(self.start_pos);
+        }
+    }
+
+    fn draw_help(&self) {
+        let settings = self.menu.settings();
+        draw_text(
+            &format!(
+                "{} / {} / seed {} - M menu  R regenerate  G generation  S solving  F free view  H heatmap  P play",
+                settings.generator.name(),
+                settings.solver.name(),
+                settings.seed
+            ),
+            10.0,
+            screen_height() - 10.0,
+            16.0,
+            WHITE,
+        );
+    }
+
+    /// Updates and draws one frame
+    pub fn frame(&mut self) {
+        let mut next_screen: Option<Screen> = None;
+
+        match &mut self.screen {
+            Screen::Menu => {
+                if self.menu.update() {
+                    self.build();
+                    next_screen = Some(self.generation_playback());
+                }
+                self.menu.draw();
+            }
+            Screen::Generation(playback, snapshot, applied) => {
+                playback.update();
+                // only rewinding replays the events from the start
+                if playback.position() < *applied {
+                    *snapshot = GenerationSnapshot::new(&self.empty_grid);
+                    *applied = 0;
+                }
+                for event in &self.events[*applied..playback.position()] {
+                    snapshot.apply(event);
+                }
+                *applied = playback.position();
+                display_generation_step(snapshot);
+                playback.draw_status();
+
+                if playback.is_finished() && !playback.is_paused() {
+                    next_screen = Some(self.solving_playback());
+                }
+            }
+            Screen::Solving(playback) => {
+                playback.update();
+                display_maze(&self.grid, self.start_pos, self.end_pos);
+                display_grid(
+                    self.mouse_path[0..playback.position()].to_vec(),
+                    &self.grid,
+                    PATH_COLOR,
+                    true,
+                );
+                playback.draw_status();
+            }
+            Screen::FreeView => display_maze(&self.grid, self.start_pos, self.end_pos),
+            Screen::Heatmap(origin, distances) => {
+                if is_mouse_button_pressed(MouseButton::Left) {
+                    let (x, y) = mouse_position();
+                    let grid_size = self.grid.get_number_of_cells_x_y();
+                    if let Some(clicked) = window_coord_to_game_coord(x, y, grid_size)
+                        && self.grid.contains(clicked)
+                    {
+                        *origin = clicked;
+                        *distances = distances_from(clicked, &self.grid);
+                    }
+                }
+                display_maze(&self.grid, self.start_pos, self.end_pos);
+                display_heatmap(&self.grid, distances);
+                display_grid(vec![*origin], &self.grid, YELLOW, false);
+            }
+            Screen::Play(play) => {
+                play.update(&self.grid);
+                if play.is_fogged() {
+                    play.draw_fogged_maze(&self.grid, self.start_pos);
+                } else {
+                    display_maze(&self.grid, self.start_pos, self.end_pos);
+                }
+                play.draw(&self.grid, &self.shortest_path);
+            }
+        }
+
+        if let Some(screen) = next_screen {
+            self.screen = screen;
+        } else if !matches!(self.screen, Screen::Menu) {
+            self.handle_screen_keys();
+            self.draw_help();
+        }
+    }
+}
@@ -1,17 +1,24 @@
 use macroquad::{
-    color::{Color, GRAY, GREEN, PURPLE, RED},
+    color::{Color, DARKGRAY, GRAY, GREEN, PURPLE, RED, SKYBLUE},
     window::Conf,
 };
 
 pub const WINDOW_SIZE_X: usize = 600;
 pub const WINDOW_SIZE_Y: usize = 600;
 
-pub const WALL_RATIO: f64 = 0.05;
+pub use explorer::constants::render_constants::WALL_RATIO;
 
 pub const CELL_COLOR: Color = GRAY;
 pub const PATH_COLOR: Color = GREEN;
 pub const LEP_PATH_COLOR: Color = PURPLE;
 pub const ACCENT_COLOR: Color = RED;
+pub const PLAYER_COLOR: Color = SKYBLUE;
+// cells seen before but out of sight in fog of war
+pub const REMEMBERED_CELL_COLOR: Color = DARKGRAY;
+
+// default playback speeds of the animations, in steps per second
+pub const GENERATION_STEPS_PER_SECOND: f32 = 120.0;
+pub const PATH_STEPS_PER_SECOND: f32 = 30.0;
 
 pub fn get_window_config() -> Conf {
     Conf {
@@ -0,0 +1,220 @@
+use macroquad::prelude::*;
+
+use crate::gui::constants::{ACCENT_COLOR, CELL_COLOR};
+use explorer::analysis::Endpoints;
+use explorer::constants::game_constants::{
+    MAX_NUMBER_OF_CELLS, MIN_NUMBER_OF_CELLS, NUMBER_OF_CELLS_X, NUMBER_OF_CELLS_Y,
+};
+use explorer::maze_generator::Generator;
+use explorer::mouse::Solver;
+
+/// Outline of the maze
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Shape {
+    Rectangle,
+    Heart,
+}
+
+impl Shape {
+    pub const ALL: [Shape; 2] = [Shape::Rectangle, Shape::Heart];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shape::Rectangle => "rectangle",
+            Shape::Heart => "heart",
+        }
+    }
+}
+
+/// Everything needed to build and solve a maze
+#[derive(Debug, Clone)]
+pub struct Settings {
+    pub number_of_cells_x: u16,
+    pub number_of_cells_y: u16,
+    pub shape: Shape,
+    pub generator: Generator,
+    /// Open rooms carved before the corridors, fewer when they don't fit
+    pub rooms: usize,
+    pub solver: Solver,
+    pub endpoints: Endpoints,
+    /// Times every dead end is removed after the generation
+    pub sparsify_passes: usize,
+    /// Fraction of the dead ends opened after the generation
+    pub braid: f64,
+    /// Highest cost of the rough terrain scattered over the maze, 1 for none
+    pub max_cost: u32,
+    pub seed: u64,
+    pub fog_of_war: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Self {
+            number_of_cells_x: NUMBER_OF_CELLS_X,
+            number_of_cells_y: NUMBER_OF_CELLS_Y,
+            shape: Shape::Rectangle,
+            generator: Generator::Wilson,
+            rooms: 0,
+            solver: Solver::Bfs,
+            endpoints: Endpoints::Corners,
+            sparsify_passes: 0,
+            braid: 0.0,
+            max_cost: 1,
+            seed: ::rand::random(),
+            fog_of_war: false,
+        }
+    }
+}
+
+const ROOM_COUNTS: [usize; 4] = [0, 2, 4, 8];
+const SPARSIFY_PASSES: [usize; 6] = [0, 1, 2, 5, 10, 20];
+const BRAID_LEVELS: [f64; 5] = [0.0, 0.25, 0.5, 0.75, 1.0];
+const MAX_COSTS: [u32; 4] = [1, 3, 5, 9];
+
+const ENTRIES: [&str; 13] = [
+    "Width",
+    "Height",
+    "Shape",
+    "Generator",
+    "Rooms",
+    "Solver",
+    "Endpoints",
+    "Sparsify",
+    "Braid",
+    "Terrain",
+    "Seed",
+    "Fog of war",
+    "Start",
+];
+
+// moves `current` by `step` in `values`, wrapping around
+fn cycle<T: Copy + PartialEq>(values: &[T], current: T, step: isize) -> T {
+    let index = values
+        .iter()
+        .position(|value| *value == current)
+        .unwrap_or(0);
+    let len = values.len() as isize;
+    values[(index as isize + step).rem_euclid(len) as usize]
+}
+
+/// Keyboard driven settings menu.
+///
+/// - Up / Down: select an entry
+/// - Left / Right: change its value
+/// - R: random seed
+/// - Enter: start
+pub struct Menu {
+    settings: Settings,
+    selected: usize,
+}
+
+impl Menu {
+    pub fn new(settings: Settings) -> Menu {
+        Self {
+            settings,
+            selected: 0,
+        }
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+
+    /// Handles the controls, returns true once the user asked to start
+    pub fn update(&mut self) -> bool {
+        if is_key_pressed(KeyCode::Down) {
+            self.selected = (self.selected + 1) % ENTRIES.len();
+        }
+        if is_key_pressed(KeyCode::Up) {
+            self.selected = (self.selected + ENTRIES.len() - 1) % ENTRIES.len();
+        }
+        if is_key_pressed(KeyCode::R) {
+            self.settings.seed = ::rand::random();
+        }
+
+        let mut step: isize = 0;
+        if is_key_pressed(KeyCode::Right) {
+            step += 1;
+        }
+        if is_key_pressed(KeyCode::Left) {
+            step -= 1;
+        }
+        if step != 0 {
+            self.change_selected(step);
+        }
+
+        is_key_pressed(KeyCode::Enter)
+    }
+
+    fn change_selected(&mut self, step: isize) {
+        let settings = &mut self.settings;
+        let resize = |size: u16| {
+            (size as isize + step).clamp(MIN_NUMBER_OF_CELLS as isize, MAX_NUMBER_OF_CELLS as isize)
+                as u16
+        };
+        match self.selected {
+            0 => settings.number_of_cells_x = resize(settings.number_of_cells_x),
+            1 => settings.number_of_cells_y = resize(settings.number_of_cells_y),
+            2 => settings.shape = cycle(&Shape::ALL, settings.shape, step),
+            3 => settings.generator = cycle(&Generator::ALL, settings.generator, step),
+            4 => settings.rooms = cycle(&ROOM_COUNTS, settings.rooms, step),
+            5 => settings.solver = cycle(&Solver::ALL, settings.solver, step),
+            6 => settings.endpoints = cycle(&Endpoints::ALL, settings.endpoints, step),
+            7 => settings.sparsify_passes = cycle(&SPARSIFY_PASSES, settings.sparsify_passes, step),
+            8 => settings.braid = cycle(&BRAID_LEVELS, settings.braid, step),
+            9 => settings.max_cost = cycle(&MAX_COSTS, settings.max_cost, step),
+            10 => settings.seed = settings.seed.wrapping_add_signed(step as i64),
+            11 => settings.fog_of_war = !settings.fog_of_war,
+            _ => {}
+        }
+    }
+
+    pub fn draw(&self) {
+        clear_background(BLACK);
+        let settings = &self.settings;
+        let values = [
+            settings.number_of_cells_x.to_string(),
+            settings.number_of_cells_y.to_string(),
+            settings.shape.name().to_owned(),
+            settings.generator.name().to_owned(),
+            match settings.rooms {
+                0 => "none".to_owned(),
+                rooms => rooms.to_string(),
+            },
+            settings.solver.name().to_owned(),
+            settings.endpoints.name().to_owned(),
+            format!("{} passes", settings.sparsify_passes),
+            format!("{}%", settings.braid * 100.0),
+            match settings.max_cost {
+                1 => "flat".to_owned(),
+                max_cost => format!("costs up to {}", max_cost),
+            },
+            settings.seed.to_string(),
+            if settings.fog_of_war { "on" } else { "off" }.to_owned(),
+            String::new(),
+        ];
+
+        draw_text("Explorer", 40.0, 60.0, 40.0, WHITE);
+        for (i, (entry, value)) in ENTRIES.iter().zip(values.iter()).enumerate() {
+            let color = if i == self.selected {
+                ACCENT_COLOR
+            } else {
+                CELL_COLOR
+            };
+            let y = 110.0 + i as f32 * 31.0;
+            draw_text(entry, 40.0, y, 30.0, color);
+            draw_text(value, 220.0, y, 30.0, color);
+        }
+        draw_text(
+            "Up/Down select - Left/Right change - R random seed - Enter start",
+            40.0,
+            530.0,
+            18.0,
+            WHITE,
+        );
+    }
+}
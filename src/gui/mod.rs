@@ -0,0 +1,25 @@
+pub mod app;
+pub mod constants;
+pub mod menu;
+pub mod play;
+pub mod playback;
+pub mod render;
+
+use macroquad::prelude::*;
+
+use app::App;
+use constants::get_window_config;
+use menu::Settings;
+
+async fn run_viewer() {
+    let mut app = App::new(Settings::default());
+    loop {
+        app.frame();
+        next_frame().await
+    }
+}
+
+/// Opens the viewer window, only returns once it is closed
+pub fn open_viewer() {
+    macroquad::Window::from_config(get_window_config(), run_viewer());
+}
@@ -0,0 +1,179 @@
+use macroquad::prelude::*;
+
+use std::collections::HashSet;
+
+use crate::gui::constants::{
+    ACCENT_COLOR, CELL_COLOR, PATH_COLOR, PLAYER_COLOR, REMEMBERED_CELL_COLOR,
+};
+use crate::gui::render::display_grid;
+use explorer::model::cell::Position;
+use explorer::model::grid::Grid;
+
+/// A game where the user walks from the start to the exit.
+///
+/// Arrow keys or WASD move the player, walls can't be crossed. With fog of
+/// war, only the cells the player has seen are drawn.
+pub struct Play {
+    // every cell the player went through, starting with the start cell
+    trail: Vec<Position>,
+    end_pos: Position,
+    fog_of_war: bool,
+    seen: HashSet<Position>,
+    started_at: f64,
+    finished_at: Option<f64>,
+}
+
+impl Play {
+    pub fn new(start_pos: Position, end_pos: Position, fog_of_war: bool, grid: &Grid) -> Play {
+        let mut play = Self {
+            trail: vec![start_pos],
+            end_pos,
+            fog_of_war,
+            seen: HashSet::new(),
+            started_at: get_time(),
+            finished_at: None,
+        };
+        play.seen.extend(play.visible_positions(grid));
+        play
+    }
+
+    /// The player's cell and every cell down the straight corridors leaving it
+    pub fn visible_positions(&self, grid: &Grid) -> Vec<Position> {
+        let position = self.position();
+        let mut visible: Vec<Position> = vec![position];
+        let steps: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+        for (step_x, step_y) in steps {
+            let mut current_pos = position;
+            while let (Some(x), Some(y)) = (
+                current_pos.x.checked_add_signed(step_x),
+                current_pos.y.checked_add_signed(step_y),
+            ) {
+                let next_pos = Position { x, y };
+                if !grid.are_neighbors(current_pos, next_pos) {
+                    break;
+                }
+                visible.push(next_pos);
+                current_pos = next_pos;
+            }
+        }
+        visible
+    }
+
+    pub fn position(&self) -> Position {
+        *self.trail.last().unwrap()
+    }
+
+    pub fn moves(&self) -> usize {
+        self.trail.len() - 1
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished_at.is_some()
+    }
+
+    /// Seconds since the start, frozen once the exit is reached
+    pub fn elapsed(&self) -> f64 {
+        self.finished_at.unwrap_or_else(get_time) - self.started_at
+    }
+
+    fn pressed_move(&self) -> Option<Position> {
+        let position = self.position();
+        if is_key_pressed(KeyCode::Left) || is_key_pressed(KeyCode::A) {
+            return position
+                .x
+                .checked_sub(1)
+                .map(|x| Position { x, y: position.y });
+        }
+        if is_key_pressed(KeyCode::Up) || is_key_pressed(KeyCode::W) {
+            return position
+                .y
+                .checked_sub(1)
+                .map(|y| Position { x: position.x, y });
+        }
+        if is_key_pressed(KeyCode::Right) || is_key_pressed(KeyCode::D) {
+            return Some(Position {
+                x: position.x + 1,
+                y: position.y,
+            });
+        }
+        if is_key_pressed(KeyCode::Down) || is_key_pressed(KeyCode::S) {
+            return Some(Position {
+                x: position.x,
+                y: position.y + 1,
+            });
+        }
+        None
+    }
+
+    /// Moves the player according to the keys pressed this frame
+    pub fn update(&mut self, grid: &Grid) {
+        if self.is_finished() {
+            return;
+        }
+        if let Some(new_pos) = self.pressed_move()
+            && grid.are_neighbors(self.position(), new_pos)
+        {
+            self.trail.push(new_pos);
+            self.seen.extend(self.visible_positions(grid));
+            if new_pos == self.end_pos {
+                self.finished_at = Some(get_time());
+            }
+        }
+    }
+
+    /// Whether the maze should be drawn with `draw_fogged_maze` instead of
+    /// in full. The fog lifts once the exit is reached.
+    pub fn is_fogged(&self) -> bool {
+        self.fog_of_war && !self.is_finished()
+    }
+
+    /// Draws the cells in sight, and the ones seen before dimmed
+    pub fn draw_fogged_maze(&self, grid: &Grid, start_pos: Position) {
+        clear_background(BLACK);
+        let visible = self.visible_positions(grid);
+        let remembered: Vec<Position> = grid
+            .get_cells_positions()
+            .into_iter()
+            .filter(|pos| self.seen.contains(pos) && !visible.contains(pos))
+            .collect();
+
+        display_grid(remembered, grid, REMEMBERED_CELL_COLOR, false);
+        display_grid(visible, grid, CELL_COLOR, false);
+        if self.seen.contains(&start_pos) {
+            display_grid(vec![start_pos], grid, YELLOW, false);
+        }
+        if self.seen.contains(&self.end_pos) {
+            display_grid(vec![self.end_pos], grid, ACCENT_COLOR, false);
+        }
+    }
+
+    /// Draws the player over an already drawn maze. Once finished, the
+    /// shortest path is shown next to the player's one.
+    pub fn draw(&self, grid: &Grid, shortest_path: &[Position]) {
+        let optimal_moves = shortest_path.len().saturating_sub(1);
+        if self.is_finished() {
+            display_grid(shortest_path.to_vec(), grid, PATH_COLOR, false);
+        }
+        display_grid(self.trail.clone(), grid, PLAYER_COLOR, false);
+        display_grid(vec![self.position()], grid, ACCENT_COLOR, false);
+
+        let text = if self.is_finished() {
+            format!(
+                "Exit reached in {:.1}s with {} moves, the shortest path takes {} ({} extra)",
+                self.elapsed(),
+                self.moves(),
+                optimal_moves,
+                self.moves().saturating_sub(optimal_moves)
+            )
+        } else {
+            format!(
+                "time {:.1}s - moves {} - optimal {}",
+                self.elapsed(),
+                self.moves(),
+                optimal_moves
+            )
+        };
+        draw_text(&text, 10.0, 20.0, 18.0, WHITE);
+    }
+}
@@ -0,0 +1,95 @@
+use macroquad::prelude::*;
+
+const MIN_STEPS_PER_SECOND: f32 = 0.5;
+const MAX_STEPS_PER_SECOND: f32 = 2048.0;
+
+/// Keyboard controlled cursor over the steps of an animation.
+///
+/// - Space: pause / resume
+/// - Left / Right: step backward / forward (pauses)
+/// - Up / Down: double / halve the speed
+/// - Home / End: jump to the first / last step
+pub struct Playback {
+    position: usize,
+    length: usize,
+    paused: bool,
+    steps_per_second: f32,
+    // fraction of a step not played yet
+    accumulated_steps: f32,
+}
+
+impl Playback {
+    pub fn new(length: usize, steps_per_second: f32) -> Playback {
+        Self {
+            position: 0,
+            length,
+            paused: false,
+            steps_per_second,
+            accumulated_steps: 0.0,
+        }
+    }
+
+    /// Number of steps currently shown
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position == self.length
+    }
+
+    /// Handles the controls and advances by the time elapsed since the last frame
+    pub fn update(&mut self) {
+        if is_key_pressed(KeyCode::Space) {
+            self.paused = !self.paused;
+        }
+        if is_key_pressed(KeyCode::Up) {
+            self.steps_per_second = (self.steps_per_second * 2.0).min(MAX_STEPS_PER_SECOND);
+        }
+        if is_key_pressed(KeyCode::Down) {
+            self.steps_per_second = (self.steps_per_second / 2.0).max(MIN_STEPS_PER_SECOND);
+        }
+        if is_key_pressed(KeyCode::Right) {
+            self.paused = true;
+            self.position = (self.position + 1).min(self.length);
+        }
+        if is_key_pressed(KeyCode::Left) {
+            self.paused = true;
+            self.position = self.position.saturating_sub(1);
+        }
+        if is_key_pressed(KeyCode::Home) {
+            self.position = 0;
+        }
+        if is_key_pressed(KeyCode::End) {
+            self.position = self.length;
+        }
+
+        if self.paused || self.is_finished() {
+            self.accumulated_steps = 0.0;
+            return;
+        }
+
+        self.accumulated_steps += get_frame_time() * self.steps_per_second;
+        let steps = self.accumulated_steps.floor();
+        self.accumulated_steps -= steps;
+        self.position = (self.position + steps as usize).min(self.length);
+    }
+
+    pub fn draw_status(&self) {
+        let state = if self.paused { "paused" } else { "playing" };
+        draw_text(
+            &format!(
+                "{} {}/{} - {} steps/s",
+                state, self.position, self.length, self.steps_per_second
+            ),
+            10.0,
+            20.0,
+            20.0,
+            WHITE,
+        );
+    }
+}
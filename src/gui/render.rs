@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use macroquad::prelude::*;
+
+use crate::gui::constants;
+use crate::gui::constants::{ACCENT_COLOR, CELL_COLOR, LEP_PATH_COLOR, PATH_COLOR};
+use explorer::format::ImageStyle;
+use explorer::maze_generator::GenerationSnapshot;
+use explorer::model::cell::Position;
+use explorer::model::grid::Grid;
+
+pub fn game_coord_to_window_coord(x: usize, y: usize, grid_size: (u16, u16)) -> (usize, usize) {
+    let window_x: usize = x * constants::WINDOW_SIZE_X / grid_size.0 as usize;
+    let window_y: usize = y * constants::WINDOW_SIZE_Y / grid_size.1 as usize;
+
+    (window_x, window_y)
+}
+
+/// Cell under a point of the window, if any
+pub fn window_coord_to_game_coord(x: f32, y: f32, grid_size: (u16, u16)) -> Option<Position> {
+    if x < 0.0 || y < 0.0 {
+        return None;
+    }
+    let pos = Position {
+        x: x as usize * grid_size.0 as usize / constants::WINDOW_SIZE_X,
+        y: y as usize * grid_size.1 as usize / constants::WINDOW_SIZE_Y,
+    };
+    (pos.x < grid_size.0 as usize && pos.y < grid_size.1 as usize).then_some(pos)
+}
+
+pub fn display_grid(
+    positions: Vec<Position>,
+    grid: &Grid,
+    color: macroquad::color::Color,
+    accent_on_last: bool,
+) {
+    fn draw_cell(pos: Position, grid: &Grid, color: macroquad::color::Color) {
+        let (x_px, y_px) = game_coord_to_window_coord(pos.x, pos.y, grid.get_number_of_cells_x_y());
+        let (number_of_cells_x, number_of_cells_y) = grid.get_number_of_cells_x_y();
+        let cell_width = constants::WINDOW_SIZE_X / number_of_cells_x as usize;
+        let cell_height = constants::WINDOW_SIZE_Y / number_of_cells_y as usize;
+
+        let wall_thickness_width = (constants::WALL_RATIO * cell_width as f64) as usize;
+        let wall_thickness_height = (constants::WALL_RATIO * cell_height as f64) as usize;
+
+        // scaling
+        let x: isize = pos.x as isize;
+        let y: isize = pos.y as isize;
+
+        let mut offset_left: usize = 0;
+        let mut offset_top: usize = 0;
+
+        let mut rect_width = cell_width;
+        let mut rect_height = cell_height;
+
+        if !grid.are_neighbors(
+            pos,
+            Position {
+                x: (x - 1) as usize,
+                y: y as usize,
+            },
+        ) {
+            offset_left += wall_thickness_width;
+            rect_width -= wall_thickness_width;
+        }
+        if !grid.are_neighbors(
+            pos,
+            Position {
+                x: (x + 1) as usize,
+                y: y as usize,
+            },
+        ) {
+            rect_width -= wall_thickness_width;
+        }
+        if !grid.are_neighbors(
+            pos,
+            Position {
+                x: x as usize,
+                y: (y + 1) as usize,
+            },
+        ) {
+            rect_height -= wall_thickness_height;
+        }
+        if !grid.are_neighbors(
+            pos,
+            Position {
+                x: x as usize,
+                y: (y - 1) as usize,
+            },
+        ) {
+            offset_top += wall_thickness_height;
+            rect_height -= wall_thickness_height;
+        }
+
+        draw_rectangle(
+            (x_px + offset_left) as f32,
+            (y_px + offset_top) as f32,
+            rect_width as f32,
+            rect_height as f32,
+            color,
+        );
+    }
+
+    for position in positions.iter() {
+        draw_cell(*position, grid, color);
+    }
+
+    if accent_on_last && let Some(last) = positions.last() {
+        draw_cell(last.to_owned(), grid, ACCENT_COLOR);
+    }
+}
+
+/// Colors the cells by their distance to the origin, with the same gradient
+/// as the image exports
+pub fn display_heatmap(grid: &Grid, distances: &HashMap<Position, usize>) {
+    let style = ImageStyle::viewer();
+    let farthest = distances.values().max().copied().unwrap_or(0);
+    for (pos, distance) in distances {
+        let [r, g, b] = style.heatmap_color(*distance, farthest);
+        display_grid(vec![*pos], grid, Color::from_rgba(r, g, b, 255), false);
+    }
+}
+
+/// Tints the cells expensive to walk into, like the image exports
+pub fn display_costs(grid: &Grid) {
+    let style = ImageStyle::viewer();
+    let max_cost = grid.get_max_cost();
+    for pos in grid.get_cells_positions() {
+        if grid.get_cost(pos) != 1 {
+            let [r, g, b] = style.cost_color(grid.get_cost(pos), max_cost);
+            display_grid(vec![pos], grid, Color::from_rgba(r, g, b, 255), false);
+        }
+    }
+}
+
+pub fn display_generation_step(snapshot: &GenerationSnapshot) {
+    clear_background(BLACK);
+    let grid = &snapshot.grid;
+    display_grid(grid.get_cells_positions(), grid, CELL_COLOR, false);
+
+    let walk = &snapshot.loop_erased_walk;
+    display_grid(walk.to_vec(), grid, PATH_COLOR, true);
+    if let Some(start) = walk.first() {
+        display_grid(vec![*start], grid, YELLOW, false);
+    }
+    display_grid(snapshot.erased_loop.to_vec(), grid, LEP_PATH_COLOR, false);
+}
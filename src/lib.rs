@@ -0,0 +1,13 @@
+//! Maze engine: grids, generators, solvers and file formats.
+//!
+//! The viewer lives in the `explorer` binary, behind the `gui` feature, so this
+//! library can be used without pulling in a windowing stack.
+
+pub mod analysis;
+pub mod constants;
+pub mod format;
+pub mod maze_generator;
+pub mod model;
+pub mod mouse;
+pub mod uniformity;
+pub mod validation;
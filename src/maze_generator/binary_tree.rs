@@ -0,0 +1,118 @@
+use crate::maze_generator::{GenerationEvent, GenerationSteps};
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+use crate::mouse::distances_from;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+use std::collections::HashMap;
+
+/// Carves every cell towards its upper or left neighbor. Very fast but
+/// heavily biased: the top row and left column are always straight corridors.
+///
+/// On a masked grid, the cells with neither an upper nor a left neighbor each
+/// grow a separate tree, which are joined through random walls at the end.
+pub struct BinaryTreeSteps {
+    grid: Grid,
+    rng: StdRng,
+    to_visit: Vec<Position>,
+    // walls between two trees in a random order, listed once every cell is carved
+    joins: Option<Vec<(Position, Position)>>,
+    // tree of each cell, merged as they are joined
+    trees: HashMap<Position, usize>,
+    parents: Vec<usize>,
+}
+
+impl BinaryTreeSteps {
+    pub fn new(grid: Grid, seed: u64) -> BinaryTreeSteps {
+        let mut to_visit = grid.get_cells_positions();
+        // popped from the end, so the top left cell goes first
+        to_visit.reverse();
+        Self {
+            grid,
+            rng: StdRng::seed_from_u64(seed),
+            to_visit,
+            joins: None,
+            trees: HashMap::new(),
+            parents: Vec::new(),
+        }
+    }
+
+    fn list_joins(&mut self) -> Vec<(Position, Position)> {
+        for pos in self.grid.get_cells_positions() {
+            if !self.trees.contains_key(&pos) {
+                let tree = self.parents.len();
+                self.parents.push(tree);
+                for reached in distances_from(pos, &self.grid).into_keys() {
+                    self.trees.insert(reached, tree);
+                }
+            }
+        }
+
+        let mut joins: Vec<(Position, Position)> = Vec::new();
+        for pos in self.grid.get_cells_positions() {
+            for other in self.grid.get_adjacent_positions(pos) {
+                if (other.y, other.x) > (pos.y, pos.x) && self.trees[&pos] != self.trees[&other] {
+                    joins.push((pos, other));
+                }
+            }
+        }
+        joins.shuffle(&mut self.rng);
+        joins
+    }
+
+    fn root_of(&self, mut tree: usize) -> usize {
+        while self.parents[tree] != tree {
+            tree = self.parents[tree];
+        }
+        tree
+    }
+
+    fn join_once(&mut self) -> Option<GenerationEvent> {
+        if self.joins.is_none() {
+            self.joins = Some(self.list_joins());
+        }
+        while let Some((pos1, pos2)) = self.joins.as_mut()?.pop() {
+            let (tree1, tree2) = (
+                self.root_of(self.trees[&pos1]),
+                self.root_of(self.trees[&pos2]),
+            );
+            if tree1 != tree2 {
+                self.parents[tree1] = tree2;
+                self.grid.add_neighbor(pos1, pos2);
+                return Some(GenerationEvent::PassageCarved(pos1, pos2));
+            }
+        }
+        None
+    }
+}
+
+impl Iterator for BinaryTreeSteps {
+    type Item = GenerationEvent;
+
+    fn next(&mut self) -> Option<GenerationEvent> {
+        while let Some(position) = self.to_visit.pop() {
+            let mut candidates = self.grid.get_adjacent_positions(position);
+            candidates.retain(|pos| pos.x < position.x || pos.y < position.y);
+            if candidates.is_empty() {
+                continue;
+            }
+
+            let new_pos = candidates[self.rng.random_range(0..candidates.len())];
+            self.grid.add_neighbor(position, new_pos);
+            return Some(GenerationEvent::PassageCarved(position, new_pos));
+        }
+        self.join_once()
+    }
+}
+
+impl GenerationSteps for BinaryTreeSteps {
+    fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    fn into_grid(self: Box<Self>) -> Grid {
+        self.grid
+    }
+}
@@ -0,0 +1,85 @@
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+/// Opens `fraction` of the dead ends of the maze, between 0 and 1, into a
+/// cell next to them, which creates loops: `round(fraction * count)` random
+/// dead ends stop being ones, fewer only if the others can't be opened. A dead
+/// end is preferably linked to another one, removing both at once. Returns
+/// the passages carved, in order.
+pub fn braid(grid: &mut Grid, fraction: f64, seed: u64) -> Vec<(Position, Position)> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let is_dead_end = |grid: &Grid, pos: Position| grid.get_neighbors_of_pos(pos).len() == 1;
+
+    let mut dead_ends: Vec<Position> = grid
+        .get_cells_positions()
+        .into_iter()
+        .filter(|pos| is_dead_end(grid, *pos))
+        .collect();
+    dead_ends.shuffle(&mut rng);
+    let mut to_open = (fraction.clamp(0.0, 1.0) * dead_ends.len() as f64).round() as usize;
+
+    let mut carved: Vec<(Position, Position)> = Vec::new();
+    for dead_end in dead_ends {
+        // linking another dead end may have opened this one already
+        if to_open == 0 {
+            break;
+        }
+        if !is_dead_end(grid, dead_end) {
+            continue;
+        }
+        let mut candidates: Vec<Position> = grid
+            .get_adjacent_positions(dead_end)
+            .into_iter()
+            .filter(|pos| !grid.are_neighbors(dead_end, *pos))
+            .collect();
+        let dead_end_candidates: Vec<Position> = candidates
+            .iter()
+            .copied()
+            .filter(|pos| is_dead_end(grid, *pos))
+            .collect();
+        // with one dead end left to open, linking it to another would open two
+        if to_open == 1 {
+            candidates.retain(|pos| !is_dead_end(grid, *pos));
+        } else if !dead_end_candidates.is_empty() {
+            candidates = dead_end_candidates;
+        }
+        if candidates.is_empty() {
+            continue;
+        }
+        let pos = candidates[rng.random_range(0..candidates.len())];
+        to_open -= if is_dead_end(grid, pos) { 2 } else { 1 };
+        grid.add_neighbor(dead_end, pos);
+        carved.push((dead_end, pos));
+    }
+    carved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::Generator;
+
+    fn count_dead_ends(grid: &Grid) -> usize {
+        grid.get_cells_positions()
+            .into_iter()
+            .filter(|pos| grid.get_neighbors_of_pos(*pos).len() == 1)
+            .count()
+    }
+
+    #[test]
+    fn opens_the_given_fraction_of_dead_ends() {
+        for seed in 0..5 {
+            let maze = Generator::Wilson.generate(Grid::new(12, 12), seed);
+            let dead_ends = count_dead_ends(&maze);
+            for fraction in [0.0, 0.25, 0.5, 1.0] {
+                let mut grid = maze.clone();
+                braid(&mut grid, fraction, seed);
+                let opened = (fraction * dead_ends as f64).round() as usize;
+                assert_eq!(count_dead_ends(&grid), dead_ends - opened);
+            }
+        }
+    }
+}
@@ -0,0 +1,261 @@
+pub mod binary_tree;
+pub mod braid;
+pub mod recursive_backtracker;
+pub mod rooms;
+pub mod sparsify;
+pub mod terrain;
+pub mod wilson;
+
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+use crate::validation::check_perfect_maze;
+
+pub use binary_tree::BinaryTreeSteps;
+pub use recursive_backtracker::RecursiveBacktrackerSteps;
+pub use rooms::{RoomOptions, RoomsSteps};
+pub use wilson::WilsonSteps;
+
+#[derive(Debug)]
+pub struct Path {
+    cell_positions: Vec<Position>,
+}
+
+impl Path {
+    fn from_positions(positions: &[Position]) -> Path {
+        Path {
+            cell_positions: positions.to_vec(),
+        }
+    }
+
+    pub fn length(&self) -> usize {
+        self.cell_positions.len()
+    }
+
+    pub fn get_cells_positions(&self) -> Vec<Position> {
+        self.cell_positions.clone()
+    }
+}
+
+impl Clone for Path {
+    fn clone(&self) -> Path {
+        Path {
+            cell_positions: self.cell_positions.clone(),
+        }
+    }
+}
+
+/// Fine-grained step of a maze generation, in the order it happened.
+#[derive(Debug, Clone)]
+pub enum GenerationEvent {
+    /// A random walk starts from this cell
+    WalkStarted(Position),
+    /// The random walk stepped into this cell
+    WalkEntered(Position),
+    /// The walk crossed itself, these cells formed a loop and were removed from it
+    LoopErased(Vec<Position>),
+    /// The wall between the two cells was removed
+    PassageCarved(Position, Position),
+    /// The walk reached a dead end and stepped back from this cell
+    Backtracked(Position),
+    /// The cell was taken out of the maze, with its passages
+    CellRemoved(Position),
+}
+
+/// A generation in progress, yielding its events one at a time.
+///
+/// The steps own the grid they are carving so the current state can be
+/// drawn between two events.
+pub trait GenerationSteps: Iterator<Item = GenerationEvent> {
+    fn grid(&self) -> &Grid;
+
+    fn into_grid(self: Box<Self>) -> Grid;
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Generator {
+    Wilson,
+    RecursiveBacktracker,
+    BinaryTree,
+}
+
+impl Generator {
+    pub const ALL: [Generator; 3] = [
+        Generator::Wilson,
+        Generator::RecursiveBacktracker,
+        Generator::BinaryTree,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Generator::Wilson => "wilson",
+            Generator::RecursiveBacktracker => "backtracker",
+            Generator::BinaryTree => "binary-tree",
+        }
+    }
+
+    /// Starts carving `grid`, which should have no passages yet
+    pub fn steps(&self, grid: Grid, seed: u64) -> Box<dyn GenerationSteps> {
+        let steps: Box<dyn GenerationSteps> = match self {
+            Generator::Wilson => Box::new(WilsonSteps::new(grid, seed)),
+            Generator::RecursiveBacktracker => Box::new(RecursiveBacktrackerSteps::new(grid, seed)),
+            Generator::BinaryTree => Box::new(BinaryTreeSteps::new(grid, seed)),
+        };
+        if cfg!(debug_assertions) {
+            Box::new(CheckedSteps {
+                generator: *self,
+                steps,
+            })
+        } else {
+            steps
+        }
+    }
+
+    /// Carves rooms into `grid` first, then the corridors around them
+    pub fn steps_with_rooms(
+        &self,
+        grid: Grid,
+        rooms: &RoomOptions,
+        seed: u64,
+    ) -> Box<dyn GenerationSteps> {
+        Box::new(RoomsSteps::new(*self, grid, rooms, seed))
+    }
+
+    /// Carves the whole maze at once
+    pub fn generate(&self, grid: Grid, seed: u64) -> Grid {
+        let mut steps = self.steps(grid, seed);
+        steps.by_ref().for_each(drop);
+        steps.into_grid()
+    }
+}
+
+// Makes sure in debug builds that the generators only carve perfect mazes
+struct CheckedSteps {
+    generator: Generator,
+    steps: Box<dyn GenerationSteps>,
+}
+
+impl Iterator for CheckedSteps {
+    type Item = GenerationEvent;
+
+    fn next(&mut self) -> Option<GenerationEvent> {
+        let event = self.steps.next();
+        if event.is_none()
+            && let Err(error) = check_perfect_maze(self.steps.grid())
+        {
+            panic!(
+                "{} generated a broken maze: {}",
+                self.generator.name(),
+                error
+            );
+        }
+        event
+    }
+}
+
+impl GenerationSteps for CheckedSteps {
+    fn grid(&self) -> &Grid {
+        self.steps.grid()
+    }
+
+    fn into_grid(self: Box<Self>) -> Grid {
+        self.steps.into_grid()
+    }
+}
+
+/// State of a maze generation after some of its events. An animation moves it
+/// forward one event at a time with `new` and `apply`, and only replays it
+/// from the start when rewound.
+pub struct GenerationSnapshot {
+    pub grid: Grid,
+    pub walk: Vec<Position>,
+    pub loop_erased_walk: Vec<Position>,
+    // only set right after a loop erasure
+    pub erased_loop: Vec<Position>,
+}
+
+impl GenerationSnapshot {
+    pub fn new(empty_grid: &Grid) -> GenerationSnapshot {
+        GenerationSnapshot {
+            grid: empty_grid.clone(),
+            walk: Vec::new(),
+            loop_erased_walk: Vec::new(),
+            erased_loop: Vec::new(),
+        }
+    }
+
+    /// Snapshot after all the given events, to go back in a generation
+    pub fn replay(empty_grid: &Grid, events: &[GenerationEvent]) -> GenerationSnapshot {
+        let mut snapshot = GenerationSnapshot::new(empty_grid);
+        for event in events {
+            snapshot.apply(event);
+        }
+        snapshot
+    }
+
+    /// Moves the snapshot one event forward
+    pub fn apply(&mut self, event: &GenerationEvent) {
+        self.erased_loop.clear();
+        match event {
+            GenerationEvent::WalkStarted(position) => {
+                self.walk = vec![*position];
+                self.loop_erased_walk = vec![*position];
+            }
+            GenerationEvent::WalkEntered(position) => {
+                self.walk.push(*position);
+                // entering a cell of the walk is followed by the erasure of the loop
+                if !self.loop_erased_walk.contains(position) {
+                    self.loop_erased_walk.push(*position);
+                }
+            }
+            GenerationEvent::LoopErased(positions) => {
+                let kept = self.loop_erased_walk.len().saturating_sub(positions.len());
+                self.loop_erased_walk.truncate(kept);
+                self.erased_loop = positions.clone();
+            }
+            GenerationEvent::PassageCarved(from, to) => {
+                self.grid.add_neighbor(*from, *to);
+            }
+            GenerationEvent::Backtracked(_) => {
+                self.walk.pop();
+                self.loop_erased_walk.pop();
+            }
+            GenerationEvent::CellRemoved(position) => {
+                self.grid.remove_cell(*position);
+            }
+        }
+    }
+}
+
+pub struct MazeGenerationInternals {
+    paths: Vec<(Path, Path, Grid)>, // Association of random walks with their loop erased counterparts
+}
+
+impl MazeGenerationInternals {
+    pub fn get_paths(&self) -> &Vec<(Path, Path, Grid)> {
+        &self.paths
+    }
+}
+
+// Wilson's algorithm
+pub fn mazify(grid: &mut Grid, seed: u64) -> MazeGenerationInternals {
+    let mut steps = WilsonSteps::new(grid.clone(), seed);
+    let mut paths_from_wilson: Vec<(Path, Path, Grid)> = Vec::new();
+
+    while let Some(event) = steps.next() {
+        if let GenerationEvent::PassageCarved(_, to) = event
+            && steps.current_loop_erased_walk().last() == Some(&to)
+        {
+            // the walk is fully carved into the maze
+            paths_from_wilson.push((
+                Path::from_positions(steps.current_walk()),
+                Path::from_positions(steps.current_loop_erased_walk()),
+                steps.grid().clone(),
+            ));
+        }
+    }
+
+    *grid = steps.grid().clone();
+    MazeGenerationInternals {
+        paths: paths_from_wilson,
+    }
+}
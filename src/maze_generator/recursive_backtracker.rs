@@ -0,0 +1,86 @@
+use crate::maze_generator::{GenerationEvent, GenerationSteps};
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+/// Depth-first carving: walks to random unvisited cells and backtracks on
+/// dead ends. Gives long winding corridors with few dead ends.
+pub struct RecursiveBacktrackerSteps {
+    grid: Grid,
+    rng: StdRng,
+    started: bool,
+    pending: VecDeque<GenerationEvent>,
+
+    stack: Vec<Position>,
+    visited: HashSet<Position>,
+}
+
+impl RecursiveBacktrackerSteps {
+    pub fn new(grid: Grid, seed: u64) -> RecursiveBacktrackerSteps {
+        Self {
+            grid,
+            rng: StdRng::seed_from_u64(seed),
+            started: false,
+            pending: VecDeque::new(),
+            stack: Vec::new(),
+            visited: HashSet::new(),
+        }
+    }
+
+    fn start(&mut self) -> Option<GenerationEvent> {
+        self.started = true;
+        let positions = self.grid.get_cells_positions();
+        if positions.is_empty() {
+            return None;
+        }
+
+        let start = positions[self.rng.random_range(0..positions.len())];
+        self.stack.push(start);
+        self.visited.insert(start);
+        Some(GenerationEvent::WalkStarted(start))
+    }
+}
+
+impl Iterator for RecursiveBacktrackerSteps {
+    type Item = GenerationEvent;
+
+    fn next(&mut self) -> Option<GenerationEvent> {
+        if let Some(event) = self.pending.pop_front() {
+            return Some(event);
+        }
+        if !self.started {
+            return self.start();
+        }
+
+        let position = *self.stack.last()?;
+        let mut unvisited = self.grid.get_adjacent_positions(position);
+        unvisited.retain(|pos| !self.visited.contains(pos));
+
+        if unvisited.is_empty() {
+            self.stack.pop();
+            return Some(GenerationEvent::Backtracked(position));
+        }
+
+        let new_pos = unvisited[self.rng.random_range(0..unvisited.len())];
+        self.grid.add_neighbor(position, new_pos);
+        self.visited.insert(new_pos);
+        self.stack.push(new_pos);
+        self.pending
+            .push_back(GenerationEvent::WalkEntered(new_pos));
+        Some(GenerationEvent::PassageCarved(position, new_pos))
+    }
+}
+
+impl GenerationSteps for RecursiveBacktrackerSteps {
+    fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    fn into_grid(self: Box<Self>) -> Grid {
+        self.grid
+    }
+}
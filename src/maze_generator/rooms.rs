@@ -0,0 +1,354 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::maze_generator::{GenerationEvent, GenerationSteps, Generator};
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+use crate::validation::check_connected;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+// random spots tried for a room before giving up on it
+const ATTEMPTS_PER_ROOM: usize = 50;
+
+/// Rectangle of cells all open to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Room {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Room {
+    pub fn contains(&self, pos: Position) -> bool {
+        (self.x..self.x + self.width).contains(&pos.x)
+            && (self.y..self.y + self.height).contains(&pos.y)
+    }
+
+    /// Cells of the room, row by row
+    pub fn get_cells_positions(&self) -> Vec<Position> {
+        (self.y..self.y + self.height)
+            .flat_map(|y| (self.x..self.x + self.width).map(move |x| Position { x, y }))
+            .collect()
+    }
+
+    // the room with the ring of cells around it, which must stay corridors
+    fn with_margin(&self) -> Room {
+        Room {
+            x: self.x - 1,
+            y: self.y - 1,
+            width: self.width + 2,
+            height: self.height + 2,
+        }
+    }
+
+    fn touches(&self, other: &Room) -> bool {
+        let margin = self.with_margin();
+        margin.x < other.x + other.width
+            && other.x < margin.x + margin.width
+            && margin.y < other.y + other.height
+            && other.y < margin.y + margin.height
+    }
+}
+
+/// How many rooms to carve and how big, the sizes being the sides in cells
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoomOptions {
+    pub min_count: usize,
+    pub max_count: usize,
+    pub min_size: usize,
+    pub max_size: usize,
+    /// Passages between each room and the corridors, at least one
+    pub doors: usize,
+}
+
+impl Default for RoomOptions {
+    fn default() -> RoomOptions {
+        RoomOptions {
+            min_count: 3,
+            max_count: 6,
+            min_size: 3,
+            max_size: 6,
+            doors: 2,
+        }
+    }
+}
+
+/// Picks random spots for the rooms, with at least one corridor cell between
+/// two rooms and around each of them. Rooms which don't fit are left out, so
+/// there may be fewer than asked.
+pub fn place_rooms(grid: &Grid, options: &RoomOptions, rng: &mut StdRng) -> Vec<Room> {
+    let (number_of_cells_x, number_of_cells_y) = grid.get_number_of_cells_x_y();
+    let (width, height) = (number_of_cells_x as usize, number_of_cells_y as usize);
+    let min_size = options.min_size.max(1);
+    let count = rng.random_range(options.min_count..=options.max_count.max(options.min_count));
+
+    let mut rooms: Vec<Room> = Vec::new();
+    for _ in 0..count {
+        for _ in 0..ATTEMPTS_PER_ROOM {
+            let room_width = rng.random_range(min_size..=options.max_size.max(min_size));
+            let room_height = rng.random_range(min_size..=options.max_size.max(min_size));
+            if room_width + 2 > width || room_height + 2 > height {
+                continue;
+            }
+            let room = Room {
+                x: rng.random_range(1..=width - room_width - 1),
+                y: rng.random_range(1..=height - room_height - 1),
+                width: room_width,
+                height: room_height,
+            };
+            if rooms.iter().any(|other| room.touches(other))
+                || !room
+                    .with_margin()
+                    .get_cells_positions()
+                    .into_iter()
+                    .all(|pos| grid.contains(pos))
+            {
+                continue;
+            }
+
+            // on a masked grid, a room could cut the corridors in two
+            rooms.push(room);
+            if corridors_connected(grid, &rooms) {
+                break;
+            }
+            rooms.pop();
+        }
+    }
+    rooms
+}
+
+// whether the cells out of the rooms can all reach each other
+fn corridors_connected(grid: &Grid, rooms: &[Room]) -> bool {
+    let in_room = |pos: Position| rooms.iter().any(|room| room.contains(pos));
+    let corridors: Vec<Position> = grid
+        .get_cells_positions()
+        .into_iter()
+        .filter(|pos| !in_room(*pos))
+        .collect();
+    let Some(first) = corridors.first() else {
+        return false;
+    };
+
+    let mut reached: HashSet<Position> = HashSet::from([*first]);
+    let mut to_visit: VecDeque<Position> = VecDeque::from([*first]);
+    while let Some(pos) = to_visit.pop_front() {
+        for adjacent in grid.get_adjacent_positions(pos) {
+            if !in_room(adjacent) && reached.insert(adjacent) {
+                to_visit.push_back(adjacent);
+            }
+        }
+    }
+    reached.len() == corridors.len()
+}
+
+/// Carves rectangular rooms, fully open inside, then runs a generator on the
+/// cells around them and opens a few doors between each room and the
+/// corridors.
+///
+/// The corridors alone are a perfect maze, the rooms and their doors add loops.
+pub struct RoomsSteps {
+    grid: Grid,
+    generator: Generator,
+    rng: StdRng,
+    rooms: Vec<Room>,
+    doors: usize,
+    // passages inside the rooms, carved first
+    room_passages: Vec<(Position, Position)>,
+    // carving a grid without the room cells
+    corridors: Box<dyn GenerationSteps>,
+    // listed once the corridors are carved
+    door_passages: Option<Vec<(Position, Position)>>,
+}
+
+impl RoomsSteps {
+    pub fn new(generator: Generator, grid: Grid, options: &RoomOptions, seed: u64) -> RoomsSteps {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let rooms = place_rooms(&grid, options, &mut rng);
+
+        let mut room_passages: Vec<(Position, Position)> = Vec::new();
+        let mut corridor_grid = grid.clone();
+        for room in rooms.iter() {
+            for pos in room.get_cells_positions() {
+                corridor_grid.remove_cell(pos);
+                let right = Position {
+                    x: pos.x + 1,
+                    y: pos.y,
+                };
+                let down = Position {
+                    x: pos.x,
+                    y: pos.y + 1,
+                };
+                for other in [right, down] {
+                    if room.contains(other) {
+                        room_passages.push((pos, other));
+                    }
+                }
+            }
+        }
+        // popped from the end
+        room_passages.reverse();
+
+        RoomsSteps {
+            grid,
+            generator,
+            rng,
+            rooms,
+            doors: options.doors.max(1),
+            room_passages,
+            corridors: generator.steps(corridor_grid, seed),
+            door_passages: None,
+        }
+    }
+
+    pub fn rooms(&self) -> &[Room] {
+        &self.rooms
+    }
+
+    fn list_door_passages(&mut self) -> Vec<(Position, Position)> {
+        let mut door_passages: Vec<(Position, Position)> = Vec::new();
+        for room in self.rooms.iter() {
+            let mut walls: Vec<(Position, Position)> = room
+                .get_cells_positions()
+                .into_iter()
+                .flat_map(|pos| {
+                    self.grid
+                        .get_adjacent_positions(pos)
+                        .into_iter()
+                        .filter(|other| !room.contains(*other))
+                        .map(move |other| (pos, other))
+                })
+                .collect();
+            walls.shuffle(&mut self.rng);
+            walls.truncate(self.doors);
+            door_passages.extend(walls);
+        }
+        door_passages.reverse();
+        door_passages
+    }
+}
+
+impl Iterator for RoomsSteps {
+    type Item = GenerationEvent;
+
+    fn next(&mut self) -> Option<GenerationEvent> {
+        if let Some((pos1, pos2)) = self.room_passages.pop() {
+            self.grid.add_neighbor(pos1, pos2);
+            return Some(GenerationEvent::PassageCarved(pos1, pos2));
+        }
+
+        if self.door_passages.is_none() {
+            if let Some(event) = self.corridors.next() {
+                if let GenerationEvent::PassageCarved(pos1, pos2) = event {
+                    self.grid.add_neighbor(pos1, pos2);
+                }
+                return Some(event);
+            }
+            self.door_passages = Some(self.list_door_passages());
+        }
+
+        if let Some((pos1, pos2)) = self.door_passages.as_mut()?.pop() {
+            self.grid.add_neighbor(pos1, pos2);
+            return Some(GenerationEvent::PassageCarved(pos1, pos2));
+        }
+
+        // the corridors were checked to be a perfect maze on their own, the
+        // rooms have loops but must still be reachable
+        if cfg!(debug_assertions)
+            && let Err(error) = check_connected(&self.grid)
+        {
+            panic!(
+                "{} with rooms generated a broken maze: {}",
+                self.generator.name(),
+                error
+            );
+        }
+        None
+    }
+}
+
+impl GenerationSteps for RoomsSteps {
+    fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    fn into_grid(self: Box<Self>) -> Grid {
+        self.grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::mask::Mask;
+
+    // rooms of the seeded generation and the maze around them
+    fn generate(grid: Grid, options: &RoomOptions, seed: u64) -> (Vec<Room>, Grid) {
+        let mut steps = Box::new(RoomsSteps::new(Generator::Wilson, grid, options, seed));
+        let rooms = steps.rooms().to_vec();
+        steps.by_ref().for_each(drop);
+        (rooms, steps.into_grid())
+    }
+
+    fn passages_out(room: &Room, grid: &Grid) -> usize {
+        room.get_cells_positions()
+            .into_iter()
+            .flat_map(|pos| grid.get_neighbors_of_pos(pos))
+            .filter(|other| !room.contains(*other))
+            .count()
+    }
+
+    #[test]
+    fn carves_open_rooms_apart_from_each_other() {
+        let options = RoomOptions {
+            min_count: 4,
+            max_count: 8,
+            doors: 3,
+            ..RoomOptions::default()
+        };
+        for seed in 0..20 {
+            let (rooms, grid) = generate(Grid::new(30, 20), &options, seed);
+            assert!(!rooms.is_empty());
+            for (i, room) in rooms.iter().enumerate() {
+                // one corridor cell at least all around
+                assert!(room.x > 0 && room.y > 0);
+                assert!(room.x + room.width < 30 && room.y + room.height < 20);
+                for other in &rooms[i + 1..] {
+                    assert!(!room.touches(other), "{:?} touches {:?}", room, other);
+                }
+
+                for pos in room.get_cells_positions() {
+                    for adjacent in grid.get_adjacent_positions(pos) {
+                        if room.contains(adjacent) {
+                            assert!(grid.are_neighbors(pos, adjacent));
+                        }
+                    }
+                }
+                assert_eq!(passages_out(room, &grid), options.doors);
+            }
+            check_connected(&grid).unwrap();
+        }
+    }
+
+    #[test]
+    fn keeps_a_masked_maze_connected() {
+        let mask = Mask::heart(30, 30).unwrap();
+        let mut number_of_rooms = 0;
+        for seed in 0..20 {
+            let (rooms, grid) = generate(Grid::from_mask(&mask), &RoomOptions::default(), seed);
+            for room in &rooms {
+                assert!(
+                    room.with_margin()
+                        .get_cells_positions()
+                        .into_iter()
+                        .all(|pos| mask.is_enabled(pos))
+                );
+                assert_eq!(passages_out(room, &grid), RoomOptions::default().doors);
+            }
+            check_connected(&grid).unwrap();
+            number_of_rooms += rooms.len();
+        }
+        assert!(number_of_rooms > 0);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+
+/// Removes every dead end of the maze, `passes` times over, leaving void
+/// cells around sparse corridors. A perfect maze stays perfect since only
+/// the ends of its branches go. Stops before the last cells would be
+/// removed. Returns the removed cells, in order.
+pub fn sparsify(grid: &mut Grid, passes: usize) -> Vec<Position> {
+    let mut removed: Vec<Position> = Vec::new();
+    for _ in 0..passes {
+        let positions = grid.get_cells_positions();
+        let dead_ends: Vec<Position> = positions
+            .iter()
+            .copied()
+            .filter(|pos| grid.get_neighbors_of_pos(*pos).len() == 1)
+            .collect();
+        if dead_ends.is_empty() || dead_ends.len() == positions.len() {
+            break;
+        }
+        for pos in dead_ends {
+            grid.remove_cell(pos);
+            removed.push(pos);
+        }
+    }
+    removed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::Generator;
+    use crate::validation::check_perfect_maze;
+
+    fn dead_ends(grid: &Grid) -> Vec<Position> {
+        grid.get_cells_positions()
+            .into_iter()
+            .filter(|pos| grid.get_neighbors_of_pos(*pos).len() == 1)
+            .collect()
+    }
+
+    #[test]
+    fn removes_the_dead_ends_of_each_pass() {
+        let mut grid = Generator::Wilson.generate(Grid::new(10, 10), 5);
+        for _ in 0..3 {
+            let expected = dead_ends(&grid);
+            assert_eq!(sparsify(&mut grid, 1), expected);
+            assert!(expected.iter().all(|pos| !grid.contains(*pos)));
+            check_perfect_maze(&grid).unwrap();
+        }
+    }
+
+    #[test]
+    fn keeps_the_last_cells() {
+        let mut grid = Generator::Wilson.generate(Grid::new(4, 1), 5);
+        // the ends of the corridor go, the two cells left are both dead ends
+        assert_eq!(sparsify(&mut grid, 10).len(), 2);
+        assert_eq!(grid.get_cells_positions().len(), 2);
+    }
+}
@@ -0,0 +1,33 @@
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// one pool for about this many cells
+const CELLS_PER_POOL: usize = 40;
+const MAX_POOL_RADIUS: usize = 3;
+
+/// Scatters pools of rough terrain, like mud or water, over the maze: each
+/// pool is a disc of cells costing from 2 to `max_cost` to walk into, the
+/// most expensive pool winning where they overlap.
+pub fn scatter_terrain(grid: &mut Grid, max_cost: u32, seed: u64) {
+    let positions = grid.get_cells_positions();
+    if max_cost < 2 || positions.is_empty() {
+        return;
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    for _ in 0..positions.len().div_ceil(CELLS_PER_POOL) {
+        let center = positions[rng.random_range(0..positions.len())];
+        let radius = rng.random_range(1..=MAX_POOL_RADIUS);
+        let cost = rng.random_range(2..=max_cost);
+        let in_pool = |pos: &Position| {
+            let (dx, dy) = (pos.x.abs_diff(center.x), pos.y.abs_diff(center.y));
+            // rounder than a diamond or a square on small radii
+            dx * dx + dy * dy <= radius * radius + radius
+        };
+        for pos in positions.iter().filter(|pos| in_pool(pos)) {
+            grid.set_cost(*pos, grid.get_cost(*pos).max(cost));
+        }
+    }
+}
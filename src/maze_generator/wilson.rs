@@ -0,0 +1,149 @@
+use crate::maze_generator::{GenerationEvent, GenerationSteps};
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use std::collections::HashSet;
+use std::collections::VecDeque;
+
+// Picks a random cell next to `position`
+fn random_step(position: Position, grid: &Grid, rng: &mut StdRng) -> Position {
+    let adjacent = grid.get_adjacent_positions(position);
+    adjacent[rng.random_range(0..adjacent.len())]
+}
+
+enum Phase {
+    PickStart,
+    Walking,
+    Carving(usize),
+    Done,
+}
+
+/// Wilson's algorithm, run one event at a time.
+///
+/// The generator owns the grid it is carving so the current state can be
+/// drawn between two events.
+pub struct WilsonSteps {
+    grid: Grid,
+    rng: StdRng,
+    phase: Phase,
+    pending: VecDeque<GenerationEvent>,
+
+    to_visit: Vec<Position>,
+    visited: HashSet<Position>,
+    walk: Vec<Position>,
+    loop_erased_walk: Vec<Position>,
+}
+
+impl WilsonSteps {
+    pub fn new(grid: Grid, seed: u64) -> WilsonSteps {
+        Self {
+            to_visit: grid.get_cells_positions(),
+            grid,
+            rng: StdRng::seed_from_u64(seed),
+            phase: Phase::PickStart,
+            pending: VecDeque::new(),
+            visited: HashSet::new(),
+            walk: Vec::new(),
+            loop_erased_walk: Vec::new(),
+        }
+    }
+
+    /// Every cell the current walk went through, loops included
+    pub fn current_walk(&self) -> &[Position] {
+        &self.walk
+    }
+
+    /// The current walk once its loops have been erased
+    pub fn current_loop_erased_walk(&self) -> &[Position] {
+        &self.loop_erased_walk
+    }
+
+    fn pick_start(&mut self) -> Option<GenerationEvent> {
+        if self.to_visit.is_empty() {
+            self.phase = Phase::Done;
+            return None;
+        }
+
+        let index = self.rng.random_range(0..self.to_visit.len());
+        let start = self.to_visit.swap_remove(index);
+        self.walk = vec![start];
+        self.loop_erased_walk = vec![start];
+
+        if self.visited.is_empty() {
+            // the first cell is the root of the maze, there is nothing to walk to yet
+            self.visited.insert(start);
+            self.phase = Phase::Carving(0);
+        } else {
+            self.phase = Phase::Walking;
+        }
+        Some(GenerationEvent::WalkStarted(start))
+    }
+
+    fn walk_once(&mut self) -> GenerationEvent {
+        let position = *self.walk.last().unwrap();
+        let new_pos = random_step(position, &self.grid, &mut self.rng);
+        self.walk.push(new_pos);
+
+        if let Some(index) = self.loop_erased_walk.iter().position(|pos| *pos == new_pos) {
+            let erased = self.loop_erased_walk.split_off(index + 1);
+            self.pending.push_back(GenerationEvent::LoopErased(erased));
+        } else {
+            self.loop_erased_walk.push(new_pos);
+        }
+
+        if self.visited.contains(&new_pos) {
+            self.phase = Phase::Carving(0);
+        }
+        GenerationEvent::WalkEntered(new_pos)
+    }
+
+    fn carve_once(&mut self, i: usize) -> Option<GenerationEvent> {
+        if i + 1 >= self.loop_erased_walk.len() {
+            self.phase = Phase::PickStart;
+            return None;
+        }
+
+        let (from, to) = (self.loop_erased_walk[i], self.loop_erased_walk[i + 1]);
+        self.grid.add_neighbor(from, to);
+        self.visited.insert(from);
+        if let Some(index) = self.to_visit.iter().position(|pos| *pos == from) {
+            self.to_visit.swap_remove(index);
+        }
+        self.phase = Phase::Carving(i + 1);
+        Some(GenerationEvent::PassageCarved(from, to))
+    }
+}
+
+impl Iterator for WilsonSteps {
+    type Item = GenerationEvent;
+
+    fn next(&mut self) -> Option<GenerationEvent> {
+        if let Some(event) = self.pending.pop_front() {
+            return Some(event);
+        }
+
+        loop {
+            let event = match self.phase {
+                Phase::PickStart => return self.pick_start(),
+                Phase::Walking => Some(self.walk_once()),
+                Phase::Carving(i) => self.carve_once(i),
+                Phase::Done => return None,
+            };
+            if event.is_some() {
+                return event;
+            }
+        }
+    }
+}
+
+impl GenerationSteps for WilsonSteps {
+    fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    fn into_grid(self: Box<Self>) -> Grid {
+        self.grid
+    }
+}
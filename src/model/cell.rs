@@ -1,15 +1,9 @@
-#[derive(Debug, Eq, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Position {
     pub x: usize,
     pub y: usize,
 }
 
-impl PartialEq for Position {
-    fn eq(&self, other: &Position) -> bool {
-        self.x == other.x && self.y == other.y
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct Cell {
     position: Position,
@@ -18,7 +12,7 @@ pub struct Cell {
 impl Cell {
     pub fn new(x: usize, y: usize) -> Cell {
         Self {
-            position: Position { x: x, y: y },
+            position: Position { x, y },
         }
     }
 
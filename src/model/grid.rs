@@ -2,6 +2,7 @@ type GridDimensionsIntegerType = u16;
 
 use crate::model::cell::Cell;
 use crate::model::cell::Position;
+use crate::model::mask::Mask;
 use std::collections::HashMap;
 
 use std::clone::Clone;
@@ -12,6 +13,8 @@ pub struct Grid {
 
     cells_per_pos: HashMap<Position, Cell>,
     neighbors: HashMap<Position, Vec<Position>>,
+    // only the cells which don't cost 1
+    costs: HashMap<Position, u32>,
 }
 
 impl Grid {
@@ -22,21 +25,69 @@ impl Grid {
         let mut cells_per_pos = HashMap::new();
         for y in 0..number_of_cells_y as usize {
             for x in 0..number_of_cells_x as usize {
-                let pos = Position { x: x, y: y };
+                let pos = Position { x, y };
                 cells_per_pos.insert(pos, Cell::new(x, y));
             }
         }
 
         Self {
-            number_of_cells_x: number_of_cells_x,
-            number_of_cells_y: number_of_cells_y,
-            cells_per_pos: cells_per_pos,
+            number_of_cells_x,
+            number_of_cells_y,
+            cells_per_pos,
             neighbors: HashMap::new(),
+            costs: HashMap::new(),
         }
     }
 
+    /// Grid with only the cells enabled in the mask, the others being void
+    pub fn from_mask(mask: &Mask) -> Grid {
+        let (number_of_cells_x, number_of_cells_y) = mask.get_number_of_cells_x_y();
+        let mut grid = Grid::new(number_of_cells_x, number_of_cells_y);
+        for pos in grid.get_cells_positions() {
+            if !mask.is_enabled(pos) {
+                grid.remove_cell(pos);
+            }
+        }
+        grid
+    }
+
+    // sorted row by row so that seeded generations are reproducible
     pub fn get_cells_positions(&self) -> Vec<Position> {
-        self.cells_per_pos.keys().cloned().collect()
+        let mut positions: Vec<Position> = self.cells_per_pos.keys().cloned().collect();
+        positions.sort_by_key(|pos| (pos.y, pos.x));
+        positions
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        self.cells_per_pos.contains_key(&pos)
+    }
+
+    /// Cells orthogonally next to `pos`, whether or not a wall separates them
+    pub fn get_adjacent_positions(&self, pos: Position) -> Vec<Position> {
+        let mut adjacent: Vec<Position> = Vec::new();
+        if pos.x > 0 {
+            adjacent.push(Position {
+                x: pos.x - 1,
+                y: pos.y,
+            });
+        }
+        if pos.y > 0 {
+            adjacent.push(Position {
+                x: pos.x,
+                y: pos.y - 1,
+            });
+        }
+        adjacent.push(Position {
+            x: pos.x + 1,
+            y: pos.y,
+        });
+        adjacent.push(Position {
+            x: pos.x,
+            y: pos.y + 1,
+        });
+
+        adjacent.retain(|adjacent_pos| self.contains(*adjacent_pos));
+        adjacent
     }
 
     pub fn get_number_of_cells_x_y(
@@ -47,34 +98,90 @@ impl Grid {
 
     pub fn add_neighbor(&mut self, pos1: Position, pos2: Position) {
         if pos1.x.abs_diff(pos2.x) + pos1.y.abs_diff(pos2.y) > 1 {
-            println!("{}", pos1.x.abs_diff(pos2.x) + pos1.y.abs_diff(pos2.y));
             panic!("{:?}, {:?} shouldn't be neighbors!!", pos1, pos2);
         }
 
-        if !self.neighbors.contains_key(&pos1) {
-            self.neighbors.insert(pos1, Vec::new());
-        }
+        self.neighbors.entry(pos1).or_default().push(pos2);
+        self.neighbors.entry(pos2).or_default().push(pos1);
+    }
 
-        if !self.neighbors.contains_key(&pos2) {
-            self.neighbors.insert(pos2, Vec::new());
+    /// Checked version of `add_neighbor`, for passages read from a file: both
+    /// cells must be in the maze, next to each other and not joined yet
+    pub fn try_add_neighbor(&mut self, pos1: Position, pos2: Position) -> Result<(), String> {
+        for pos in [pos1, pos2] {
+            if !self.contains(pos) {
+                return Err(format!("cell ({}, {}) is out of the maze", pos.x, pos.y));
+            }
+        }
+        if pos1 == pos2 {
+            return Err(format!("passage from ({}, {}) to itself", pos1.x, pos1.y));
+        }
+        if pos1.x.abs_diff(pos2.x) + pos1.y.abs_diff(pos2.y) != 1 {
+            return Err(format!(
+                "({}, {}) and ({}, {}) are not next to each other",
+                pos1.x, pos1.y, pos2.x, pos2.y
+            ));
+        }
+        if self.are_neighbors(pos1, pos2) {
+            return Err(format!(
+                "passage between ({}, {}) and ({}, {}) listed twice",
+                pos1.x, pos1.y, pos2.x, pos2.y
+            ));
         }
+        self.add_neighbor(pos1, pos2);
+        Ok(())
+    }
 
-        match self.neighbors.get_mut(&pos1) {
-            Some(neighbors) => {
-                neighbors.push(pos2);
-            }
-            None => {
-                self.neighbors.insert(pos1, vec![pos2]);
-            }
-        };
-        match self.neighbors.get_mut(&pos2) {
-            Some(neighbors) => {
-                neighbors.push(pos1);
-            }
-            None => {
-                self.neighbors.insert(pos2, vec![pos1]);
+    /// Takes the cell out of the maze, along with its passages. The position
+    /// stays in the bounds of the grid as a void cell.
+    pub fn remove_cell(&mut self, pos: Position) {
+        self.cells_per_pos.remove(&pos);
+        self.costs.remove(&pos);
+        for neighbor in self.neighbors.remove(&pos).unwrap_or_default() {
+            if let Some(neighbors) = self.neighbors.get_mut(&neighbor) {
+                neighbors.retain(|other| *other != pos);
             }
-        };
+        }
+    }
+
+    /// Positions in the bounds of the grid which aren't cells of the maze,
+    /// row by row
+    pub fn get_void_positions(&self) -> Vec<Position> {
+        (0..self.number_of_cells_y as usize)
+            .flat_map(|y| (0..self.number_of_cells_x as usize).map(move |x| Position { x, y }))
+            .filter(|pos| !self.contains(*pos))
+            .collect()
+    }
+
+    /// Whether a wall is drawn between two adjacent positions: at least one of
+    /// them is a cell of the maze and no passage joins them
+    pub fn has_wall_between(&self, pos1: Position, pos2: Position) -> bool {
+        (self.contains(pos1) || self.contains(pos2)) && !self.are_neighbors(pos1, pos2)
+    }
+
+    /// Cost of walking into the cell, like mud or stairs slowing it down.
+    /// 1 unless set otherwise.
+    pub fn get_cost(&self, pos: Position) -> u32 {
+        self.costs.get(&pos).copied().unwrap_or(1)
+    }
+
+    pub fn set_cost(&mut self, pos: Position, cost: u32) {
+        if cost == 1 {
+            self.costs.remove(&pos);
+        } else {
+            self.costs.insert(pos, cost);
+        }
+    }
+
+    /// Cost of the most expensive cell, 1 if they all cost 1
+    pub fn get_max_cost(&self) -> u32 {
+        self.costs.values().copied().max().unwrap_or(1).max(1)
+    }
+
+    // a passage only listed from one of its cells, which `add_neighbor` can't make
+    #[cfg(test)]
+    pub(crate) fn add_one_way_neighbor(&mut self, from: Position, to: Position) {
+        self.neighbors.entry(from).or_default().push(to);
     }
 
     pub fn are_neighbors(&self, pos1: Position, pos2: Position) -> bool {
@@ -104,6 +211,46 @@ impl Clone for Grid {
             number_of_cells_y: self.number_of_cells_y,
             cells_per_pos: self.cells_per_pos.clone(),
             neighbors: self.neighbors.clone(),
+            costs: self.costs.clone(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_passages_which_cannot_be() {
+        let mut grid = Grid::new(3, 2);
+        grid.remove_cell(Position { x: 2, y: 0 });
+        let (a, b) = (Position { x: 0, y: 0 }, Position { x: 1, y: 0 });
+        grid.try_add_neighbor(a, b).unwrap();
+        assert!(grid.are_neighbors(a, b) && grid.are_neighbors(b, a));
+
+        let error = |grid: &mut Grid, x1, y1, x2, y2| {
+            grid.try_add_neighbor(Position { x: x1, y: y1 }, Position { x: x2, y: y2 })
+                .unwrap_err()
+        };
+        assert_eq!(
+            error(&mut grid, 0, 0, 0, 0),
+            "passage from (0, 0) to itself"
+        );
+        assert_eq!(
+            error(&mut grid, 1, 0, 0, 0),
+            "passage between (1, 0) and (0, 0) listed twice"
+        );
+        assert_eq!(
+            error(&mut grid, 0, 0, 1, 1),
+            "(0, 0) and (1, 1) are not next to each other"
+        );
+        assert_eq!(
+            error(&mut grid, 1, 0, 2, 0),
+            "cell (2, 0) is out of the maze"
+        );
+        assert_eq!(
+            error(&mut grid, 2, 1, 3, 1),
+            "cell (3, 1) is out of the maze"
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use std::collections::VecDeque;
+
+use crate::model::cell::Position;
+
+/// Which positions of a grid are cells of the maze, to give it any shape.
+/// The cells must be connected for a maze to go through all of them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mask {
+    number_of_cells_x: u16,
+    number_of_cells_y: u16,
+    // row by row
+    enabled: Vec<bool>,
+}
+
+impl Mask {
+    pub fn new(
+        number_of_cells_x: u16,
+        number_of_cells_y: u16,
+        enabled: Vec<bool>,
+    ) -> Result<Mask, String> {
+        if enabled.len() != number_of_cells_x as usize * number_of_cells_y as usize {
+            return Err(format!(
+                "{} values for a {}x{} mask",
+                enabled.len(),
+                number_of_cells_x,
+                number_of_cells_y
+            ));
+        }
+        let mask = Mask {
+            number_of_cells_x,
+            number_of_cells_y,
+            enabled,
+        };
+        mask.check_connected()?;
+        Ok(mask)
+    }
+
+    /// Reads a text template, one character per cell: `.` and spaces are
+    /// void, any other character is a cell. Short lines are padded with void.
+    ///
+    /// ```text
+    /// .##.##.
+    /// #######
+    /// .#####.
+    /// ..###..
+    /// ...#...
+    /// ```
+    pub fn from_ascii(text: &str) -> Result<Mask, String> {
+        let lines: Vec<Vec<char>> = text
+            .lines()
+            .map(|line| line.trim_end().chars().collect())
+            .collect();
+        // blank lines around the shape don't count
+        let Some(first) = lines.iter().position(|line| !line.is_empty()) else {
+            return Err("empty mask".to_owned());
+        };
+        let last = lines
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .unwrap_or(first);
+        let lines = &lines[first..=last];
+
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let number_of_cells_x =
+            u16::try_from(width).map_err(|_| "the mask is too wide".to_owned())?;
+        let number_of_cells_y =
+            u16::try_from(lines.len()).map_err(|_| "the mask is too high".to_owned())?;
+        let enabled = lines
+            .iter()
+            .flat_map(|line| {
+                (0..width).map(|x| !matches!(line.get(x).copied().unwrap_or(' '), ' ' | '.'))
+            })
+            .collect();
+        Mask::new(number_of_cells_x, number_of_cells_y, enabled)
+    }
+
+    /// Heart filling a grid of the given size
+    pub fn heart(number_of_cells_x: u16, number_of_cells_y: u16) -> Result<Mask, String> {
+        let (width, height) = (number_of_cells_x as f64, number_of_cells_y as f64);
+        let mut enabled: Vec<bool> = Vec::new();
+        for y in 0..number_of_cells_y {
+            for x in 0..number_of_cells_x {
+                // center of the cell, in the [-1.15, 1.15] x [-0.95, 1.2] frame of the curve
+                let u = 2.3 * ((x as f64 + 0.5) / width - 0.5);
+                let v = 1.2 - 2.15 * (y as f64 + 0.5) / height;
+                let curve = (u * u + v * v - 1.0).powi(3) - u * u * v.powi(3);
+                enabled.push(curve <= 0.0);
+            }
+        }
+        Mask::new(number_of_cells_x, number_of_cells_y, enabled)
+    }
+
+    pub fn get_number_of_cells_x_y(&self) -> (u16, u16) {
+        (self.number_of_cells_x, self.number_of_cells_y)
+    }
+
+    pub fn is_enabled(&self, pos: Position) -> bool {
+        pos.x < self.number_of_cells_x as usize
+            && pos.y < self.number_of_cells_y as usize
+            && self.enabled[pos.y * self.number_of_cells_x as usize + pos.x]
+    }
+
+    fn check_connected(&self) -> Result<(), String> {
+        let width = self.number_of_cells_x as usize;
+        let Some(first) = self.enabled.iter().position(|enabled| *enabled) else {
+            return Err("the mask has no cell".to_owned());
+        };
+
+        let mut reached = vec![false; self.enabled.len()];
+        reached[first] = true;
+        let mut to_visit: VecDeque<usize> = VecDeque::from([first]);
+        while let Some(index) = to_visit.pop_front() {
+            let (x, y) = (index % width, index / width);
+            let adjacent = [
+                x.checked_sub(1).map(|x| Position { x, y }),
+                y.checked_sub(1).map(|y| Position { x, y }),
+                Some(Position { x: x + 1, y }),
+                Some(Position { x, y: y + 1 }),
+            ];
+            for pos in adjacent.into_iter().flatten() {
+                let index = pos.y * width + pos.x;
+                if self.is_enabled(pos) && !reached[index] {
+                    reached[index] = true;
+                    to_visit.push_back(index);
+                }
+            }
+        }
+
+        match (0..self.enabled.len()).find(|index| self.enabled[*index] && !reached[*index]) {
+            Some(index) => Err(format!(
+                "the cells of the mask aren't connected: ({}, {}) can't be reached from ({}, {})",
+                index % width,
+                index / width,
+                first % width,
+                first / width
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze_generator::Generator;
+    use crate::model::grid::Grid;
+    use crate::validation::check_perfect_maze;
+
+    fn enabled_positions(mask: &Mask) -> Vec<Position> {
+        let (number_of_cells_x, number_of_cells_y) = mask.get_number_of_cells_x_y();
+        (0..number_of_cells_y as usize)
+            .flat_map(|y| (0..number_of_cells_x as usize).map(move |x| Position { x, y }))
+            .filter(|pos| mask.is_enabled(*pos))
+            .collect()
+    }
+
+    #[test]
+    fn pads_short_lines_with_void() {
+        let mask = Mask::from_ascii("\n###\n#\n##\n\n").unwrap();
+        assert_eq!(mask.get_number_of_cells_x_y(), (3, 3));
+        let void: Vec<Position> = [(1, 1), (2, 1), (2, 2)]
+            .into_iter()
+            .map(|(x, y)| Position { x, y })
+            .collect();
+        for pos in void {
+            assert!(!mask.is_enabled(pos), "{:?}", pos);
+        }
+        assert_eq!(enabled_positions(&mask).len(), 6);
+    }
+
+    #[test]
+    fn refuses_cells_which_are_not_connected() {
+        assert_eq!(
+            Mask::from_ascii("##.#\n##..\n").unwrap_err(),
+            "the cells of the mask aren't connected: (3, 0) can't be reached from (0, 0)"
+        );
+        assert_eq!(
+            Mask::from_ascii("..\n..\n").unwrap_err(),
+            "the mask has no cell"
+        );
+        assert_eq!(Mask::from_ascii("\n\n").unwrap_err(), "empty mask");
+    }
+
+    #[test]
+    fn generates_a_maze_over_the_enabled_cells() {
+        let mask = Mask::heart(16, 14).unwrap();
+        let grid = Generator::Wilson.generate(Grid::from_mask(&mask), 3);
+        assert_eq!(grid.get_cells_positions(), enabled_positions(&mask));
+        check_perfect_maze(&grid).unwrap();
+    }
+
+    #[test]
+    fn draws_a_heart() {
+        let mask = Mask::heart(20, 20).unwrap();
+        // empty corners, a dip between the lobes and a tip at the bottom
+        for (x, y) in [(0, 0), (19, 0), (0, 19), (19, 19), (10, 0)] {
+            assert!(!mask.is_enabled(Position { x, y }), "({}, {})", x, y);
+        }
+        for (x, y) in [(5, 4), (14, 4), (10, 10), (10, 19)] {
+            assert!(mask.is_enabled(Position { x, y }), "({}, {})", x, y);
+        }
+    }
+}
@@ -1,2 +1,3 @@
+pub mod cell;
 pub mod grid;
-pub mod cell;
\ No newline at end of file
+pub mod mask;
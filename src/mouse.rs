@@ -1,4 +1,6 @@
-use std::{collections::HashMap, thread::sleep, time::Duration};
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
 
 use crate::model::{cell::Position, grid::Grid};
 
@@ -6,17 +8,19 @@ pub trait Algo {
     fn solve(&self, end_pos: Position, grid: &Grid) -> Vec<Position>;
 }
 
+/// Breadth-first exploration, walking to the cells in the order they are
+/// reached. Every step counts the same, whatever the cells cost.
 pub struct BFSMouse {
     pos: Position,
 }
 
 impl BFSMouse {
-    fn set_new_position(&mut self, position: Position) {
+    pub fn set_new_position(&mut self, position: Position) {
         self.pos = position;
     }
 
     pub fn new(pos: Position) -> BFSMouse {
-        Self { pos: pos }
+        Self { pos }
     }
 }
 
@@ -24,7 +28,7 @@ fn construct_path_to_pos(
     pos: &Position,
     previouses: &HashMap<Position, Position>,
 ) -> Vec<Position> {
-    let mut p = pos.clone();
+    let mut p = *pos;
     let mut v: Vec<Position> = Vec::new();
     loop {
         if !previouses.contains_key(&p) {
@@ -37,10 +41,66 @@ fn construct_path_to_pos(
     v
 }
 
+/// Shortest path from `start_pos` to `end_pos`, both included, in number of
+/// steps whatever the cells cost. Empty if `end_pos` can't be reached.
+pub fn shortest_path(start_pos: Position, end_pos: Position, grid: &Grid) -> Vec<Position> {
+    let mut to_visit: VecDeque<Position> = VecDeque::from([start_pos]);
+    let mut path_to_visited: HashMap<Position, Position> = HashMap::new();
+    let mut visited: HashSet<Position> = HashSet::from([start_pos]);
+
+    while let Some(pos) = to_visit.pop_front() {
+        if pos == end_pos {
+            let mut path = construct_path_to_pos(&end_pos, &path_to_visited);
+            path.push(end_pos);
+            return path;
+        }
+        for neighbor in grid.get_neighbors_of_pos(pos) {
+            if visited.insert(neighbor) {
+                path_to_visited.insert(neighbor, pos);
+                to_visit.push_back(neighbor);
+            }
+        }
+    }
+
+    Vec::new()
+}
+
+/// Number of steps from `start_pos` to every cell it can reach
+pub fn distances_from(start_pos: Position, grid: &Grid) -> HashMap<Position, usize> {
+    let mut to_visit: VecDeque<Position> = VecDeque::from([start_pos]);
+    let mut distances: HashMap<Position, usize> = HashMap::from([(start_pos, 0)]);
+
+    while let Some(pos) = to_visit.pop_front() {
+        let distance = distances[&pos];
+        for neighbor in grid.get_neighbors_of_pos(pos) {
+            if let Entry::Vacant(entry) = distances.entry(neighbor) {
+                entry.insert(distance + 1);
+                to_visit.push_back(neighbor);
+            }
+        }
+    }
+
+    distances
+}
+
+/// Turns the walk of a mouse into the path it found, by dropping every
+/// detour it walked back from.
+pub fn erase_backtracking(walk: &[Position]) -> Vec<Position> {
+    let mut path: Vec<Position> = Vec::new();
+    for pos in walk {
+        if let Some(index) = path.iter().position(|previous_pos| previous_pos == pos) {
+            path.truncate(index + 1);
+        } else {
+            path.push(*pos);
+        }
+    }
+    path
+}
+
 fn backtrack_to_current_pos(
     current_path: &mut Vec<Position>,
-    absolute_path_to_next_pos: &Vec<Position>,
-    absolute_path_to_current_pos: &Vec<Position>,
+    absolute_path_to_next_pos: &[Position],
+    absolute_path_to_current_pos: &[Position],
 ) {
     let mut index_common_pos_in_current_path: usize = 0;
     let mut index_common_pos_in_next_pos_path: usize = 0;
@@ -69,16 +129,18 @@ fn backtrack_to_current_pos(
     let mut return_to_common_root_path: Vec<Position> = Vec::new();
     let mut to_current_pos: Vec<Position> = Vec::new();
 
-    if (absolute_path_to_current_pos.len() > 0) {
+    if !absolute_path_to_current_pos.is_empty() {
         return_to_common_root_path = absolute_path_to_current_pos
             [index_common_pos_in_current_path..absolute_path_to_current_pos.len() - 1]
             .to_vec();
         return_to_common_root_path.reverse();
     }
     // println!("return to root: {:?}", return_to_common_root_path);
-    for i in index_common_pos_in_next_pos_path + 1..absolute_path_to_next_pos.len() {
-        to_current_pos.push(absolute_path_to_next_pos[i]);
-    }
+    to_current_pos.extend(
+        absolute_path_to_next_pos
+            .iter()
+            .skip(index_common_pos_in_next_pos_path + 1),
+    );
     // println!("to_current_pos: {:?}", to_current_pos);
 
     current_path.append(&mut return_to_common_root_path);
@@ -102,7 +164,7 @@ impl Algo for BFSMouse {
                 construct_path_to_pos(&next_pos, &path_to_visited);
 
             let mut absolute_path_to_current_pos: Vec<Position> = Vec::new();
-            if path.len() > 0 {
+            if !path.is_empty() {
                 absolute_path_to_current_pos =
                     construct_path_to_pos(path.last().unwrap(), &path_to_visited);
                 absolute_path_to_current_pos.push(path.last().unwrap().to_owned());
@@ -116,24 +178,203 @@ impl Algo for BFSMouse {
             path.push(next_pos);
             // println!("path: {:?}", path);
 
-            if (next_pos == end_pos) {
+            if next_pos == end_pos {
                 break;
             }
 
             let mut neighbors: Vec<Position> = grid.get_neighbors_of_pos(next_pos);
             neighbors.retain(|neighbor| {
-                !visited.contains(&neighbor) && !path_to_visited.contains_key(&neighbor)
+                !visited.contains(neighbor) && !path_to_visited.contains_key(neighbor)
             });
             for n in neighbors.iter() {
                 path_to_visited.insert(*n, next_pos);
             }
             to_visit.append(&mut neighbors);
             visited.push(next_pos);
-            println!("still {} to visit", to_visit.len());
-            println!("path size {}", path.len());
             // sleep(Duration::from_secs(1));
         }
 
         path
     }
 }
+
+/// Depth-first exploration, always taking the first open passage it hasn't
+/// been through yet.
+pub struct DFSMouse {
+    pos: Position,
+}
+
+impl DFSMouse {
+    pub fn new(pos: Position) -> DFSMouse {
+        Self { pos }
+    }
+}
+
+impl Algo for DFSMouse {
+    fn solve(&self, end_pos: Position, grid: &Grid) -> Vec<Position> {
+        let mut path: Vec<Position> = vec![self.pos];
+        let mut stack: Vec<Position> = vec![self.pos];
+        let mut visited: HashSet<Position> = HashSet::from([self.pos]);
+
+        while let Some(&current_pos) = stack.last() {
+            if current_pos == end_pos {
+                break;
+            }
+
+            let next_pos = grid
+                .get_neighbors_of_pos(current_pos)
+                .into_iter()
+                .find(|neighbor| !visited.contains(neighbor));
+            match next_pos {
+                Some(next_pos) => {
+                    visited.insert(next_pos);
+                    stack.push(next_pos);
+                    path.push(next_pos);
+                }
+                None => {
+                    // dead end, walk back
+                    stack.pop();
+                    if let Some(&previous_pos) = stack.last() {
+                        path.push(previous_pos);
+                    }
+                }
+            }
+        }
+
+        path
+    }
+}
+
+/// Dijkstra's algorithm: explores the cells cheapest to reach first, walking
+/// like the breadth-first mouse, so the path it finds costs the least.
+pub struct DijkstraMouse {
+    pos: Position,
+}
+
+impl DijkstraMouse {
+    pub fn new(pos: Position) -> DijkstraMouse {
+        Self { pos }
+    }
+}
+
+impl Algo for DijkstraMouse {
+    fn solve(&self, end_pos: Position, grid: &Grid) -> Vec<Position> {
+        let mut path: Vec<Position> = Vec::new();
+
+        // ties are broken row by row so the walk is reproducible
+        let mut to_visit: BinaryHeap<Reverse<(u64, usize, usize)>> =
+            BinaryHeap::from([Reverse((0, self.pos.y, self.pos.x))]);
+        let mut costs: HashMap<Position, u64> = HashMap::from([(self.pos, 0)]);
+        let mut visited: HashSet<Position> = HashSet::new();
+        let mut path_to_visited: HashMap<Position, Position> = HashMap::new();
+        while let Some(Reverse((cost, y, x))) = to_visit.pop() {
+            let next_pos = Position { x, y };
+            if !visited.insert(next_pos) {
+                continue;
+            }
+
+            let absolute_path_to_next_pos = construct_path_to_pos(&next_pos, &path_to_visited);
+            let mut absolute_path_to_current_pos: Vec<Position> = Vec::new();
+            if let Some(current_pos) = path.last() {
+                absolute_path_to_current_pos = construct_path_to_pos(current_pos, &path_to_visited);
+                absolute_path_to_current_pos.push(*current_pos);
+            }
+            backtrack_to_current_pos(
+                &mut path,
+                &absolute_path_to_next_pos,
+                &absolute_path_to_current_pos,
+            );
+            path.push(next_pos);
+
+            if next_pos == end_pos {
+                break;
+            }
+
+            for neighbor in grid.get_neighbors_of_pos(next_pos) {
+                let neighbor_cost = cost + grid.get_cost(neighbor) as u64;
+                if !visited.contains(&neighbor)
+                    && costs
+                        .get(&neighbor)
+                        .is_none_or(|known| neighbor_cost < *known)
+                {
+                    costs.insert(neighbor, neighbor_cost);
+                    path_to_visited.insert(neighbor, next_pos);
+                    to_visit.push(Reverse((neighbor_cost, neighbor.y, neighbor.x)));
+                }
+            }
+        }
+
+        path
+    }
+}
+
+/// Total cost of walking along the path, the first cell being free
+pub fn path_cost(path: &[Position], grid: &Grid) -> u64 {
+    path.iter()
+        .skip(1)
+        .map(|pos| grid.get_cost(*pos) as u64)
+        .sum()
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Solver {
+    Bfs,
+    Dfs,
+    Dijkstra,
+}
+
+impl Solver {
+    pub const ALL: [Solver; 3] = [Solver::Bfs, Solver::Dfs, Solver::Dijkstra];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Solver::Bfs => "bfs",
+            Solver::Dfs => "dfs",
+            Solver::Dijkstra => "dijkstra",
+        }
+    }
+
+    /// Every cell the mouse walks through going from `start_pos` to `end_pos`
+    pub fn solve(&self, start_pos: Position, end_pos: Position, grid: &Grid) -> Vec<Position> {
+        match self {
+            Solver::Bfs => BFSMouse::new(start_pos).solve(end_pos, grid),
+            Solver::Dfs => DFSMouse::new(start_pos).solve(end_pos, grid),
+            Solver::Dijkstra => DijkstraMouse::new(start_pos).solve(end_pos, grid),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pos(x: usize, y: usize) -> Position {
+        Position { x, y }
+    }
+
+    #[test]
+    fn dijkstra_takes_the_cheaper_detour() {
+        // a short way along the top row through a costly cell, and a longer
+        // one around it along the bottom row
+        let mut grid = Grid::new(3, 2);
+        grid.add_neighbor(pos(0, 0), pos(1, 0));
+        grid.add_neighbor(pos(1, 0), pos(2, 0));
+        grid.add_neighbor(pos(0, 0), pos(0, 1));
+        grid.add_neighbor(pos(0, 1), pos(1, 1));
+        grid.add_neighbor(pos(1, 1), pos(2, 1));
+        grid.add_neighbor(pos(2, 1), pos(2, 0));
+        grid.set_cost(pos(1, 0), 10);
+
+        let walk = DijkstraMouse::new(pos(0, 0)).solve(pos(2, 0), &grid);
+        let path = erase_backtracking(&walk);
+        assert_eq!(
+            path,
+            [pos(0, 0), pos(0, 1), pos(1, 1), pos(2, 1), pos(2, 0)]
+        );
+        assert_eq!(path_cost(&path, &grid), 4);
+
+        let shortest = shortest_path(pos(0, 0), pos(2, 0), &grid);
+        assert_eq!(shortest, [pos(0, 0), pos(1, 0), pos(2, 0)]);
+        assert_eq!(path_cost(&shortest, &grid), 11);
+    }
+}
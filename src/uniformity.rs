@@ -0,0 +1,351 @@
+//! Statistical check that a generator picks every maze with the same
+//! probability, on grids small enough to list all of their mazes.
+//!
+//! A perfect maze is a spanning tree of the grid. Every spanning tree is
+//! enumerated, many mazes are generated and a chi-square test compares how
+//! often each tree came out with the uniform distribution. Wilson's algorithm
+//! should pass, the recursive backtracker and the binary tree should not.
+//! Masked grids are checked the same way, over the trees of their cells.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::maze_generator::Generator;
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+
+/// Past this many spanning trees the grid is too large to check
+pub const MAX_SPANNING_TREES: usize = 1_000_000;
+/// Default number of samples for each possible maze, the chi-square test
+/// needs at least 5
+pub const DEFAULT_SAMPLES_PER_MAZE: usize = 20;
+
+// Every wall between two cells of the grid, row by row
+fn inner_walls(grid: &Grid) -> Vec<(Position, Position)> {
+    let mut walls: Vec<(Position, Position)> = Vec::new();
+    for pos in grid.get_cells_positions() {
+        for other in [
+            Position {
+                x: pos.x + 1,
+                ..pos
+            },
+            Position {
+                y: pos.y + 1,
+                ..pos
+            },
+        ] {
+            if grid.contains(other) {
+                walls.push((pos, other));
+            }
+        }
+    }
+    walls
+}
+
+/// Identifies a maze of the grid by the set of its passages, one bit per
+/// inner wall in the order of `inner_walls`
+fn maze_key(grid: &Grid, walls: &[(Position, Position)]) -> u64 {
+    walls
+        .iter()
+        .enumerate()
+        .filter(|(_, (pos1, pos2))| grid.are_neighbors(*pos1, *pos2))
+        .fold(0, |key, (i, _)| key | (1 << i))
+}
+
+fn find(parents: &[usize], mut cell: usize) -> usize {
+    while parents[cell] != cell {
+        cell = parents[cell];
+    }
+    cell
+}
+
+// Adds the spanning trees made of `key` and of walls from `index` on to `trees`
+fn enumerate_trees(
+    edges: &[(usize, usize)],
+    index: usize,
+    key: u64,
+    missing_edges: usize,
+    parents: &[usize],
+    trees: &mut Vec<u64>,
+) -> Result<(), String> {
+    if missing_edges == 0 {
+        trees.push(key);
+        if trees.len() > MAX_SPANNING_TREES {
+            return Err(format!(
+                "the grid has more than {} mazes, too many to check",
+                MAX_SPANNING_TREES
+            ));
+        }
+        return Ok(());
+    }
+    if edges.len() - index < missing_edges {
+        return Ok(());
+    }
+
+    let (cell1, cell2) = edges[index];
+    let (root1, root2) = (find(parents, cell1), find(parents, cell2));
+    if root1 != root2 {
+        let mut parents = parents.to_vec();
+        parents[root1] = root2;
+        enumerate_trees(
+            edges,
+            index + 1,
+            key | (1 << index),
+            missing_edges - 1,
+            &parents,
+            trees,
+        )?;
+    }
+    enumerate_trees(edges, index + 1, key, missing_edges, parents, trees)
+}
+
+/// Keys of every perfect maze of the grid
+fn spanning_trees(grid: &Grid) -> Result<Vec<u64>, String> {
+    let walls = inner_walls(grid);
+    if walls.len() > u64::BITS as usize {
+        let (number_of_cells_x, number_of_cells_y) = grid.get_number_of_cells_x_y();
+        return Err(format!(
+            "{}x{} grid is too large to check",
+            number_of_cells_x, number_of_cells_y
+        ));
+    }
+    let positions = grid.get_cells_positions();
+    let index = |pos: Position| positions.iter().position(|other| *other == pos).unwrap();
+    let edges: Vec<(usize, usize)> = walls
+        .iter()
+        .map(|(pos1, pos2)| (index(*pos1), index(*pos2)))
+        .collect();
+
+    let parents: Vec<usize> = (0..positions.len()).collect();
+    let mut trees: Vec<u64> = Vec::new();
+    enumerate_trees(&edges, 0, 0, positions.len() - 1, &parents, &mut trees)?;
+    Ok(trees)
+}
+
+// Lanczos approximation of ln(Γ(x))
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    let x = x - 1.0;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..]
+        .iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| {
+            sum + c / (x + i as f64 + 1.0)
+        });
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+// Regularized upper incomplete gamma function Q(a, x)
+fn upper_gamma(a: f64, x: f64) -> f64 {
+    const EPSILON: f64 = 1e-15;
+    const MAX_ITERATIONS: usize = 1_000_000;
+    if x <= 0.0 {
+        return 1.0;
+    }
+    let prefix = (-x + a * x.ln() - ln_gamma(a)).exp();
+
+    if x < a + 1.0 {
+        // series of the lower function P(a, x)
+        let mut term = 1.0 / a;
+        let mut sum = term;
+        for n in 1..MAX_ITERATIONS {
+            term *= x / (a + n as f64);
+            sum += term;
+            if term < sum * EPSILON {
+                break;
+            }
+        }
+        return (1.0 - sum * prefix).max(0.0);
+    }
+
+    // continued fraction of Q(a, x), with the modified Lentz method
+    let tiny = 1e-300;
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / tiny;
+    let mut d = 1.0 / b;
+    let mut fraction = d;
+    for n in 1..MAX_ITERATIONS {
+        let an = -(n as f64) * (n as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < tiny {
+            d = tiny;
+        }
+        c = b + an / c;
+        if c.abs() < tiny {
+            c = tiny;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        fraction *= delta;
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    fraction * prefix
+}
+
+/// Outcome of the chi-square test of a generator
+#[derive(Debug, Clone)]
+pub struct UniformityReport {
+    pub generator: Generator,
+    pub number_of_mazes: usize,
+    pub samples: usize,
+    /// Mazes which were never generated
+    pub never_generated: usize,
+    pub chi_square: f64,
+    pub degrees_of_freedom: usize,
+    /// Probability of a deviation at least this large from a uniform generator
+    pub p_value: f64,
+}
+
+impl UniformityReport {
+    /// Mean number of times each maze should come out
+    pub fn expected_count(&self) -> f64 {
+        self.samples as f64 / self.number_of_mazes as f64
+    }
+}
+
+impl fmt::Display for UniformityReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "generator: {}", self.generator.name())?;
+        writeln!(f, "possible mazes: {}", self.number_of_mazes)?;
+        writeln!(
+            f,
+            "samples: {} ({:.1} per maze)",
+            self.samples,
+            self.expected_count()
+        )?;
+        writeln!(f, "never generated: {}", self.never_generated)?;
+        writeln!(
+            f,
+            "chi-square: {:.1} with {} degrees of freedom",
+            self.chi_square, self.degrees_of_freedom
+        )?;
+        writeln!(f, "p-value: {:.4}", self.p_value)?;
+        if self.expected_count() < 5.0 {
+            writeln!(
+                f,
+                "warning: less than 5 samples per maze, the test isn't reliable"
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Generates `samples` mazes of the empty grid, which may be masked,
+/// `DEFAULT_SAMPLES_PER_MAZE` times the number of possible mazes by default,
+/// with the seeds following `seed`, and tests whether every possible maze is
+/// as likely to come out.
+pub fn check_uniformity(
+    generator: Generator,
+    empty_grid: &Grid,
+    samples: Option<usize>,
+    seed: u64,
+) -> Result<UniformityReport, String> {
+    if empty_grid.get_cells_positions().is_empty() {
+        return Err("the grid has no cell".to_owned());
+    }
+    let trees = spanning_trees(empty_grid)?;
+    let samples = samples.unwrap_or(DEFAULT_SAMPLES_PER_MAZE * trees.len());
+    if samples == 0 {
+        return Err("at least one sample is needed".to_owned());
+    }
+    let walls = inner_walls(empty_grid);
+
+    let mut counts: HashMap<u64, usize> = trees.iter().map(|key| (*key, 0)).collect();
+    for i in 0..samples as u64 {
+        let grid = generator.generate(empty_grid.clone(), seed.wrapping_add(i));
+        let key = maze_key(&grid, &walls);
+        *counts
+            .get_mut(&key)
+            .ok_or_else(|| format!("{} generated a maze with loops", generator.name()))? += 1;
+    }
+
+    let expected = samples as f64 / trees.len() as f64;
+    let chi_square = counts
+        .values()
+        .map(|count| (*count as f64 - expected).powi(2) / expected)
+        .sum::<f64>();
+    let degrees_of_freedom = trees.len() - 1;
+    Ok(UniformityReport {
+        generator,
+        number_of_mazes: trees.len(),
+        samples,
+        never_generated: counts.values().filter(|count| **count == 0).count(),
+        chi_square,
+        degrees_of_freedom,
+        p_value: upper_gamma(degrees_of_freedom as f64 / 2.0, chi_square / 2.0),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(value: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (value - expected).abs() < tolerance,
+            "{} instead of {}",
+            value,
+            expected
+        );
+    }
+
+    #[test]
+    fn counts_the_spanning_trees_of_small_grids() {
+        assert_eq!(spanning_trees(&Grid::new(2, 2)).unwrap().len(), 4);
+        assert_eq!(spanning_trees(&Grid::new(3, 3)).unwrap().len(), 192);
+        // a path has a single tree
+        assert_eq!(spanning_trees(&Grid::new(5, 1)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn computes_known_gamma_values() {
+        assert_close(ln_gamma(1.0), 0.0, 1e-12);
+        assert_close(ln_gamma(5.0), 24f64.ln(), 1e-12);
+        assert_close(ln_gamma(0.5), std::f64::consts::PI.sqrt().ln(), 1e-12);
+        for x in [0.1, 1.0, 2.5, 20.0] {
+            assert_close(upper_gamma(1.0, x), (-x).exp(), 1e-12);
+        }
+    }
+
+    #[test]
+    fn computes_chi_square_p_values() {
+        // critical values of the chi-square distribution, by degrees of freedom
+        for (degrees_of_freedom, chi_square, p_value) in [
+            (1, 3.841, 0.05),
+            (2, 9.210, 0.01),
+            (4, 13.277, 0.01),
+            (10, 18.307, 0.05),
+            (100, 124.342, 0.05),
+        ] {
+            let value = upper_gamma(degrees_of_freedom as f64 / 2.0, chi_square / 2.0);
+            assert_close(value, p_value, 1e-4);
+        }
+    }
+
+    #[test]
+    fn tells_uniform_generators_apart() {
+        let grid = Grid::new(3, 3);
+        let wilson = check_uniformity(Generator::Wilson, &grid, None, 1).unwrap();
+        assert_eq!(wilson.samples, DEFAULT_SAMPLES_PER_MAZE * 192);
+        assert_eq!(wilson.never_generated, 0);
+        assert!(wilson.p_value > 0.01, "{}", wilson);
+
+        let binary_tree = check_uniformity(Generator::BinaryTree, &grid, None, 1).unwrap();
+        assert!(binary_tree.never_generated > 0);
+        assert!(binary_tree.p_value < 1e-9, "{}", binary_tree);
+    }
+}
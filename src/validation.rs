@@ -0,0 +1,155 @@
+//! Checks of the passages of a grid, for generated mazes and loaded files.
+
+use crate::model::cell::Position;
+use crate::model::grid::Grid;
+use crate::mouse::distances_from;
+
+/// Checks that every passage links two cells of the grid next to each other,
+/// is listed once and from both of its cells.
+pub fn check_links(grid: &Grid) -> Result<(), String> {
+    let mut links: Vec<(&Position, &Vec<Position>)> = grid.get_neighbors().iter().collect();
+    links.sort_by_key(|(pos, _)| (pos.y, pos.x));
+
+    for (pos, neighbors) in links {
+        if !grid.contains(*pos) {
+            return Err(format!("cell ({}, {}) is out of the maze", pos.x, pos.y));
+        }
+        for (i, neighbor) in neighbors.iter().enumerate() {
+            let passage = format!(
+                "passage between ({}, {}) and ({}, {})",
+                pos.x, pos.y, neighbor.x, neighbor.y
+            );
+            if !grid.contains(*neighbor) {
+                return Err(format!("{} leads out of the maze", passage));
+            }
+            if pos.x.abs_diff(neighbor.x) + pos.y.abs_diff(neighbor.y) != 1 {
+                return Err(format!("{} links cells which aren't adjacent", passage));
+            }
+            if neighbors[..i].contains(neighbor) {
+                return Err(format!("{} is listed twice", passage));
+            }
+            if !grid.are_neighbors(*neighbor, *pos) {
+                return Err(format!("{} only goes one way", passage));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the passages are valid and that every cell can be reached,
+/// loops being allowed.
+pub fn check_connected(grid: &Grid) -> Result<(), String> {
+    check_links(grid)?;
+
+    let positions = grid.get_cells_positions();
+    let Some(first) = positions.first() else {
+        return Ok(());
+    };
+    let distances = distances_from(*first, grid);
+    if let Some(unreachable) = positions.iter().find(|pos| !distances.contains_key(pos)) {
+        return Err(format!(
+            "cell ({}, {}) can't be reached from ({}, {})",
+            unreachable.x, unreachable.y, first.x, first.y
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the grid is a perfect maze: valid passages, every cell
+/// reachable and no loops, so there is exactly one path between two cells.
+pub fn check_perfect_maze(grid: &Grid) -> Result<(), String> {
+    check_connected(grid)?;
+    let positions = grid.get_cells_positions();
+    if positions.is_empty() {
+        return Ok(());
+    }
+
+    // a connected graph without loops has one edge less than vertices
+    let number_of_passages = grid
+        .get_neighbors()
+        .values()
+        .map(|neighbors| neighbors.len())
+        .sum::<usize>()
+        / 2;
+    if number_of_passages != positions.len() - 1 {
+        return Err(format!(
+            "{} passages for {} cells, the maze has loops",
+            number_of_passages,
+            positions.len()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::ascii;
+
+    fn pos(x: usize, y: usize) -> Position {
+        Position { x, y }
+    }
+
+    // 2x2 grid open along the top row and down both columns
+    fn u_shaped_maze() -> Grid {
+        let mut grid = Grid::new(2, 2);
+        grid.add_neighbor(pos(0, 0), pos(1, 0));
+        grid.add_neighbor(pos(0, 0), pos(0, 1));
+        grid.add_neighbor(pos(1, 0), pos(1, 1));
+        grid
+    }
+
+    #[test]
+    fn accepts_a_perfect_maze() {
+        assert_eq!(check_perfect_maze(&u_shaped_maze()), Ok(()));
+    }
+
+    #[test]
+    fn rejects_an_unreachable_cell() {
+        let mut grid = Grid::new(2, 2);
+        grid.add_neighbor(pos(0, 0), pos(1, 0));
+        grid.add_neighbor(pos(1, 0), pos(1, 1));
+
+        let error = check_perfect_maze(&grid).unwrap_err();
+        assert_eq!(error, "cell (0, 1) can't be reached from (0, 0)");
+        assert!(check_connected(&grid).is_err());
+    }
+
+    #[test]
+    fn rejects_a_loop() {
+        let mut grid = u_shaped_maze();
+        grid.add_neighbor(pos(0, 1), pos(1, 1));
+
+        let error = check_perfect_maze(&grid).unwrap_err();
+        assert_eq!(error, "4 passages for 4 cells, the maze has loops");
+        assert_eq!(check_connected(&grid), Ok(()));
+    }
+
+    #[test]
+    fn rejects_a_one_way_passage() {
+        let mut grid = Grid::new(2, 1);
+        grid.add_one_way_neighbor(pos(0, 0), pos(1, 0));
+
+        let error = check_links(&grid).unwrap_err();
+        assert_eq!(error, "passage between (0, 0) and (1, 0) only goes one way");
+        assert!(check_perfect_maze(&grid).is_err());
+    }
+
+    #[test]
+    fn rejects_a_walled_in_cell_of_a_loaded_file() {
+        // the ring around the middle cell is a tree, the middle cell is closed
+        let text = "\
++--+--+--+
+|        |
++  +--+--+
+|  |  |  |
++  +--+  +
+|        |
++--+--+--+
+";
+        let grid = ascii::parse(text).unwrap();
+        assert!(grid.contains(pos(1, 1)));
+        let error = check_perfect_maze(&grid).unwrap_err();
+        assert_eq!(error, "cell (1, 1) can't be reached from (0, 0)");
+    }
+}